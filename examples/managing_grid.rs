@@ -1,24 +1,5 @@
 use grid_engine::grid_engine::GridEngine;
 
-#[derive(Clone, Debug)]
-struct GridContent {
-    id: String,
-}
-
-impl Default for GridContent {
-    fn default() -> Self {
-        GridContent {
-            id: "0".to_string(),
-        }
-    }
-}
-
-impl std::fmt::Display for GridContent {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.id)
-    }
-}
-
 fn print_grid(grid: &GridEngine) {
     let mut grid_str_formatted = String::new();
     grid_str_formatted.push_str("  ");
@@ -27,11 +8,12 @@ fn print_grid(grid: &GridEngine) {
     }
     grid_str_formatted.push('\n');
 
-    grid.get_inner_grid()
-        .iter_rows()
-        .enumerate()
-        .for_each(|(row_number, row)| {
-            row.enumerate().for_each(|(index, cell)| {
+    let inner_grid = grid.get_inner_grid();
+    (0..inner_grid.rows()).for_each(|row_number| {
+        inner_grid
+            .iter_row_ids(row_number)
+            .enumerate()
+            .for_each(|(index, cell)| {
                 if index == 0 {
                     grid_str_formatted.push_str(&format!("{:0>2}", row_number));
                 }
@@ -44,8 +26,8 @@ fn print_grid(grid: &GridEngine) {
                     }
                 };
             });
-            grid_str_formatted.push('\n');
-        });
+        grid_str_formatted.push('\n');
+    });
 
     println!("{}", grid_str_formatted);
 }
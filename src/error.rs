@@ -1,3 +1,4 @@
+use crate::geometry::Axis;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,18 +9,32 @@ pub enum GridEngineError {
     #[error(transparent)]
     Item(#[from] ItemError),
 
-    // Temporary error for unhandled errors, must be removed and all errors should be handled
-    #[error("UnhandledError: {0}")]
-    Unhandled(Box<dyn std::error::Error>),
+    #[error("Changes vetoed by a pre-change listener")]
+    ChangesVetoed,
+
+    #[error("Grid is frozen")]
+    Frozen,
+
+    #[error("Grid needs at least one column: rows: {rows}, cols: {cols}")]
+    NoColumns { rows: usize, cols: usize },
+
+    #[error(transparent)]
+    GridEvent(#[from] GridEventError),
 }
 
 #[derive(Error, Debug)]
 pub enum InnerGridError {
-    #[error("Out of bounds access: x: {x}, y: {y}")]
-    OutOfBoundsAccess { x: usize, y: usize },
+    #[error("Out of bounds access on the {axis} axis: x: {x}, y: {y}")]
+    OutOfBoundsAccess { x: usize, y: usize, axis: Axis },
 
     #[error("RawGrid item not matching grid items: id: {id}")]
     MismatchedGridItem { id: String },
+
+    #[error("Item in the footer can't be pushed down, nor up: {id}")]
+    FooterPushBlocked { id: String },
+
+    #[error("No node handle left to place: {id}")]
+    HandlesExhausted { id: String },
 }
 
 #[derive(Error, Debug)]
@@ -29,10 +44,28 @@ pub enum ItemError {
 
     #[error("Item already exists: {id}")]
     ItemAlreadyExists { id: String },
+
+    #[error("Position occupied by: {ids:?}")]
+    PositionOccupied { ids: Vec<String> },
+
+    #[error("Invalid item dimensions: w: {w}, h: {h}")]
+    InvalidDimensions { w: usize, h: usize },
+
+    #[error("Item too wide: w: {w}, cols: {cols}")]
+    TooWide { w: usize, cols: usize },
+
+    #[error("Item straddles row {row}: {id}")]
+    StraddlesRow { id: String, row: usize },
+
+    #[error("Item cells don't form a rectangle: {id}")]
+    NotRectangular { id: String },
 }
 
 #[derive(Error, Debug)]
 pub enum GridEventError {
     #[error("Failed to generate listener id")]
     ListenerIdNotGenerated,
+
+    #[error("Listener id already taken: {id}")]
+    DuplicateListenerId { id: String },
 }
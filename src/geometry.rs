@@ -0,0 +1,112 @@
+// Copyright (c) 2025 Thiago Ramos
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Typed coordinates and dimensions.
+//!
+//! Throughout the crate, `x` is the column and `y` is the row, counted from the
+//! top-left corner of the grid. [`Position`] and [`Size`] carry that convention
+//! in their types, so a position can't be passed where a size is expected, or
+//! the other way around.
+
+/// A cell coordinate, `x` being the column and `y` the row.
+///
+/// # Example
+///
+/// ```
+/// use grid_engine::geometry::Position;
+///
+/// let position = Position::new(3, 1);
+/// assert_eq!(position, Position::from((3, 1)));
+/// assert_eq!((position.x, position.y), (3, 1));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Position {
+    /// The column
+    pub x: usize,
+    /// The row
+    pub y: usize,
+}
+
+impl Position {
+    /// Creates a new position from its column and row.
+    pub fn new(x: usize, y: usize) -> Self {
+        Position { x, y }
+    }
+}
+
+impl From<(usize, usize)> for Position {
+    /// Creates a position from an `(x, y)` tuple.
+    fn from((x, y): (usize, usize)) -> Self {
+        Position::new(x, y)
+    }
+}
+
+/// Dimensions in cells, `w` spanning columns and `h` spanning rows.
+///
+/// # Example
+///
+/// ```
+/// use grid_engine::geometry::Size;
+///
+/// let size = Size::new(2, 4);
+/// assert_eq!(size, Size::from((2, 4)));
+/// assert_eq!((size.w, size.h), (2, 4));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Size {
+    /// The number of columns spanned
+    pub w: usize,
+    /// The number of rows spanned
+    pub h: usize,
+}
+
+impl Size {
+    /// Creates a new size from its width and height.
+    pub fn new(w: usize, h: usize) -> Self {
+        Size { w, h }
+    }
+}
+
+impl From<(usize, usize)> for Size {
+    /// Creates a size from a `(w, h)` tuple.
+    fn from((w, h): (usize, usize)) -> Self {
+        Size::new(w, h)
+    }
+}
+
+/// One of the two axes of the grid.
+///
+/// Displayed as `x` or `y`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Axis {
+    /// The horizontal axis, along the columns
+    X,
+    /// The vertical axis, along the rows
+    Y,
+}
+
+impl std::fmt::Display for Axis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Axis::X => write!(f, "x"),
+            Axis::Y => write!(f, "y"),
+        }
+    }
+}
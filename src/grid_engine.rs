@@ -49,14 +49,21 @@
 //! ```
 
 use crate::error::{GridEngineError, InnerGridError, ItemError};
+use crate::geometry::{Axis, Position, Size};
 use crate::grid_events::{ChangesEventValue, GridEvents};
+pub use crate::inner_grid::AutoGrowPolicy;
 use crate::inner_grid::{InnerGrid, UpdateGridOperation};
 use crate::node::Node;
-use crate::utils::{ForCellArgs, for_cell};
-use std::{collections::BTreeMap, fmt::Debug};
+use crate::utils::{for_cell, map_indices};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+};
 
 /// Represents data for an item addition change
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddChangeData {
     /// The node being added to the grid
     value: Node,
@@ -84,6 +91,7 @@ impl AddChangeData {
 
 /// Represents data for an item removal change
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RemoveChangeData {
     /// The node being removed from the grid
     value: Node,
@@ -111,6 +119,7 @@ impl RemoveChangeData {
 
 /// Represents data for an item movement change
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MoveChangeData {
     /// The original state of the node
     old_value: Node,
@@ -147,8 +156,97 @@ impl MoveChangeData {
     }
 }
 
+/// Represents data for an item resize change
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResizeChangeData {
+    /// The original state of the node
+    old_value: Node,
+    /// The new state of the node after resizing
+    new_value: Node,
+}
+
+impl ResizeChangeData {
+    /// Creates a new ResizeChangeData instance
+    ///
+    /// # Arguments
+    ///
+    /// * `old_value` - The original state of the node
+    /// * `new_value` - The new state of the node after resizing
+    ///
+    /// # Returns
+    ///
+    /// A new instance of ResizeChangeData
+    pub fn new(old_value: Node, new_value: Node) -> Self {
+        Self {
+            old_value,
+            new_value,
+        }
+    }
+
+    /// Returns the original state of the node
+    pub fn old_value(&self) -> &Node {
+        &self.old_value
+    }
+
+    /// Returns the new state of the node after resizing
+    pub fn new_value(&self) -> &Node {
+        &self.new_value
+    }
+}
+
+/// Represents data for an item reorder change, where its geometry stays the
+/// same and only other attributes, its creation sequence number or its
+/// weight, change
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReorderChangeData {
+    /// The original state of the node
+    old_value: Node,
+    /// The new state of the node after reordering
+    new_value: Node,
+}
+
+impl ReorderChangeData {
+    /// Creates a new ReorderChangeData instance
+    ///
+    /// # Arguments
+    ///
+    /// * `old_value` - The original state of the node
+    /// * `new_value` - The new state of the node after reordering
+    ///
+    /// # Returns
+    ///
+    /// A new instance of ReorderChangeData
+    pub fn new(old_value: Node, new_value: Node) -> Self {
+        Self {
+            old_value,
+            new_value,
+        }
+    }
+
+    /// Returns the original state of the node
+    pub fn old_value(&self) -> &Node {
+        &self.old_value
+    }
+
+    /// Returns the new state of the node after reordering
+    pub fn new_value(&self) -> &Node {
+        &self.new_value
+    }
+}
+
 /// Represents different types of changes that can occur in the grid
+///
+/// New kinds of changes may be added in the future, so matches outside this
+/// crate need a wildcard arm.
+///
+/// With the `serde` feature enabled, changes serialize as objects tagged by a
+/// `type` field holding the variant name.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+#[non_exhaustive]
 pub enum Change {
     /// Adding a new item to the grid
     Add(AddChangeData),
@@ -156,815 +254,8435 @@ pub enum Change {
     Remove(RemoveChangeData),
     /// Moving an item to a new position
     Move(MoveChangeData),
+    /// Changing the size of an item, possibly along with its position
+    Resize(ResizeChangeData),
+    /// Changing the stacking order or the weight of an item, without moving it
+    Reorder(ReorderChangeData),
 }
 
-/// The main engine for managing a 2D grid system.
-///
-/// `GridEngine` provides functionality for:
-/// - Adding items to specific grid positions
-/// - Moving items while handling collisions
-/// - Removing items from the grid
-/// - Tracking changes through an event system
-/// - Expand the grid dynamically on the y axis
-///
-/// When items collide during placement or movement, the engine automatically
-/// repositions affected items to prevent overlapping, the default is to move the collided items down, increasing their y axis.
-#[derive(Debug)]
-pub struct GridEngine {
-    /// The underlying grid structure
-    grid: InnerGrid,
-    /// Map of item IDs to their Node representations
-    items: BTreeMap<String, Node>,
-    /// Changes waiting to be applied
-    pending_changes: Vec<Change>,
-    /// Event system for tracking grid changes
-    events: GridEvents,
-}
-
-impl GridEngine {
-    /// Creates a new GridEngine with specified dimensions.
+impl Change {
+    /// Applies this change to a snapshot, the same way the engine applied it.
+    ///
+    /// Lets consumers keep a mirror of the layout up to date from the change
+    /// events alone, without duplicating the engine's bookkeeping. The change
+    /// is applied as is, no collision is resolved.
     ///
     /// # Arguments
     ///
-    /// * `rows` - Initial number of rows in the grid
-    /// * `cols` - Initial number of columns in the grid
+    /// * `snapshot` - The mirrored layout to update
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the change was applied
+    /// * `Err(GridEngineError)` - If the change doesn't fit the snapshot's grid,
+    ///   in which case the snapshot is left untouched
     ///
     /// # Example
     ///
     /// ```
     /// use grid_engine::grid_engine::GridEngine;
+    /// use std::sync::{Arc, Mutex};
+    /// # use std::error::Error;
     ///
-    /// let grid = GridEngine::new(10, 10); // Creates a 10x10 grid
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// let mirror = Arc::new(Mutex::new(grid.snapshot()));
+    /// let mirror_clone = mirror.clone();
+    /// grid.add_changes_listener(move |event| {
+    ///     let mut mirror = mirror_clone.lock().unwrap();
+    ///     for change in event.changes() {
+    ///         change.apply_to(&mut mirror).unwrap();
+    ///     }
+    /// })?;
+    ///
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// assert_eq!(*mirror.lock().unwrap(), grid.snapshot());
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn new(rows: usize, cols: usize) -> GridEngine {
-        GridEngine {
-            grid: InnerGrid::new(rows, cols),
-            items: BTreeMap::new(),
-            pending_changes: Vec::new(),
-            events: GridEvents::default(),
+    pub fn apply_to(&self, snapshot: &mut GridSnapshot) -> Result<(), GridEngineError> {
+        let mut grid = snapshot.grid.clone();
+        let mut items = snapshot.items.clone();
+        apply_changes_to(&mut grid, &mut items, std::slice::from_ref(self))?;
+
+        snapshot.grid = grid;
+        snapshot.items = items;
+
+        Ok(())
+    }
+}
+
+/// Result of moving an item, separating it from the items its move displaced
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MoveOutcome {
+    /// The move of the requested item, a resize if it shrank to fit
+    moved: Change,
+    /// The moves of the items displaced to make room for it
+    displaced: Vec<Change>,
+}
+
+impl MoveOutcome {
+    /// Returns the move of the requested item, a resize if it shrank to fit
+    pub fn moved(&self) -> &Change {
+        &self.moved
+    }
+
+    /// Returns the moves of the items displaced to make room for the requested one
+    pub fn displaced(&self) -> &Vec<Change> {
+        &self.displaced
+    }
+}
+
+/// Result of [`GridEngine::toggle_item`]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Toggled<'a> {
+    /// The item was absent and got added, holding it as placed
+    Added(&'a Node),
+    /// The item was present and got removed, holding its last state
+    Removed(Node),
+}
+
+/// Strategy used to resolve collisions when items are added or moved
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CollisionStrategy {
+    /// Push the collided items down, increasing their y axis
+    #[default]
+    PushDown,
+    /// Swap places with the collided item when a moved item lands exactly on a
+    /// single item of the same size, otherwise falls back to [`CollisionStrategy::PushDown`]
+    Swap,
+    /// Push the collided items right, like text flow, wrapping them to the
+    /// start of the row below the incoming item when they would exceed the columns
+    FlowRight,
+    /// Push each collided item down or right, whichever moves it fewer cells
+    /// to clear the incoming item. Pushing right is only considered when the
+    /// item still fits within the columns, ties are pushed down
+    Auto,
+    /// Treat the columns spanned by an item as its lane: only items in the exact
+    /// same lane are pushed down, items in other lanes are never displaced and
+    /// the incoming item is stacked below them instead
+    StackInColumn,
+    /// Skip collision resolution entirely, letting items overlap on purpose,
+    /// e.g. for layered cards. Each cell shows the topmost item, the one
+    /// created (or brought to the front) last, see [`GridEngine::item_at`];
+    /// the items buried under it are tracked aside. Switching to another
+    /// strategy keeps existing overlaps, only new collisions are resolved
+    Stack,
+    /// Shrink the incoming item's height, down to 1, until it fits where it
+    /// was placed instead of pushing others. Cells past the row limit, see
+    /// [`GridEngine::set_max_rows`], count as taken too, so the item also
+    /// shrinks rather than overflowing a capped grid. A moved item that
+    /// shrinks is reported as a resize. When even a height of 1 doesn't fit,
+    /// collisions are resolved like [`CollisionStrategy::PushDown`]
+    ShrinkToFit,
+}
+
+/// When change listeners are notified
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum DispatchMode {
+    /// Listeners are called as soon as the changes are applied
+    #[default]
+    Immediate,
+    /// Events are queued, and listeners only called when the host drains them
+    /// with [`GridEvents::drain_events`]
+    Queued,
+    /// Changes are buffered and merged into the net change of each item, see
+    /// [`ChangesEventValue::net_changes`]. Listeners get a single event when
+    /// the host calls [`GridEvents::flush`], or as soon as `threshold` changes
+    /// are buffered. A threshold of 0 only dispatches on flush
+    Coalesced {
+        /// Number of buffered changes that triggers a dispatch
+        threshold: usize,
+    },
+}
+
+/// Where an added item goes when its requested position is occupied
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AddPlacement {
+    /// The added item takes the requested position, the existing items are
+    /// displaced according to the [`CollisionStrategy`]
+    #[default]
+    PushExisting,
+    /// The existing items stay, the added item goes to the first free position
+    /// at or below the requested one, in the same column
+    FindNextFree,
+}
+
+/// Occupancy statistics of the grid, as returned by [`GridEngine::occupancy`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridStats {
+    /// Total number of cells in the grid
+    pub total_cells: usize,
+    /// Number of cells holding an item
+    pub occupied_cells: usize,
+    /// Number of empty cells
+    pub free_cells: usize,
+    /// Ratio of occupied cells over the total, between 0.0 and 1.0
+    pub fill_ratio: f64,
+    /// Rectangle enclosing all occupied cells as (x, y, w, h), `None` when empty
+    pub bounding_box: Option<(usize, usize, usize, usize)>,
+}
+
+/// A structured summary of the layout, as returned by [`GridEngine::describe`]
+///
+/// Meant for logging and snapshot tests, it serializes with the `serde` feature.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LayoutSummary {
+    /// Number of rows in the grid
+    pub rows: usize,
+    /// Number of columns in the grid
+    pub cols: usize,
+    /// Number of items in the grid
+    pub item_count: usize,
+    /// Ratio of occupied cells over the total, between 0.0 and 1.0
+    pub occupancy_ratio: f64,
+    /// The items, sorted by id
+    pub items: Vec<NodeSummary>,
+}
+
+/// The placement of a single item within a [`LayoutSummary`]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NodeSummary {
+    /// Id of the item
+    pub id: String,
+    /// X coordinate of the item
+    pub x: usize,
+    /// Y coordinate of the item
+    pub y: usize,
+    /// Width of the item
+    pub w: usize,
+    /// Height of the item
+    pub h: usize,
+}
+
+/// A mismatch between the grid cells and the items, as reported by [`GridEngine::validate`]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum InconsistencyReport {
+    /// A cell references an id that isn't a known item
+    UnknownItem {
+        /// The id found in the cell
+        id: String,
+        /// X coordinate of the cell
+        x: usize,
+        /// Y coordinate of the cell
+        y: usize,
+    },
+    /// A cell references an item whose footprint doesn't cover it
+    StrayCell {
+        /// The id found in the cell
+        id: String,
+        /// X coordinate of the cell
+        x: usize,
+        /// Y coordinate of the cell
+        y: usize,
+    },
+    /// A cell within an item's footprint doesn't point back to it
+    MissingCell {
+        /// The id of the item owning the footprint
+        id: String,
+        /// X coordinate of the cell
+        x: usize,
+        /// Y coordinate of the cell
+        y: usize,
+        /// What the cell holds instead, `None` when empty or out of bounds
+        found: Option<String>,
+    },
+}
+
+/// Options for building a [`GridEngine`] with [`GridEngine::new_with_options`]
+///
+/// Start from [`GridOptions::new`] and override the fields that differ from
+/// the defaults.
+///
+/// # Example
+///
+/// ```
+/// use grid_engine::grid_engine::{CollisionStrategy, GridEngine, GridOptions};
+///
+/// let grid = GridEngine::new_with_options(GridOptions {
+///     can_expand_y: false,
+///     collision_strategy: CollisionStrategy::Swap,
+///     ..GridOptions::new(10, 12)
+/// });
+///
+/// assert!(!grid.can_expand_y());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct GridOptions {
+    /// Initial number of rows
+    pub rows: usize,
+    /// Initial number of columns
+    pub cols: usize,
+    /// Whether the grid grows horizontally, disabled by default
+    pub can_expand_x: bool,
+    /// Whether the grid grows vertically, enabled by default
+    pub can_expand_y: bool,
+    /// Maximum number of rows the grid can grow to, unbounded by default
+    pub max_rows: Option<usize>,
+    /// Strategy used to resolve collisions
+    pub collision_strategy: CollisionStrategy,
+    /// Where added items go when their position is occupied
+    pub add_placement: AddPlacement,
+}
+
+impl GridOptions {
+    /// Creates the default options for a grid of the given dimensions.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - Initial number of rows
+    /// * `cols` - Initial number of columns
+    pub fn new(rows: usize, cols: usize) -> Self {
+        GridOptions {
+            rows,
+            cols,
+            can_expand_x: false,
+            can_expand_y: true,
+            max_rows: None,
+            collision_strategy: CollisionStrategy::default(),
+            add_placement: AddPlacement::default(),
         }
     }
+}
 
-    /// Creates a new node with the specified parameters.
-    fn new_node(&mut self, id: impl Into<String>, x: usize, y: usize, w: usize, h: usize) -> Node {
-        Node::new(id.into(), x, y, w, h)
+/// Options for rendering the grid as text with [`GridEngine::get_grid_formatted_with`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct GridFormatOptions {
+    /// Character opening each cell
+    pub open: char,
+    /// Character closing each cell
+    pub close: char,
+    /// Character filling empty cells and padding short ids
+    pub empty: char,
+    /// Minimum width of a cell's content, longer ids are not truncated
+    pub cell_width: usize,
+}
+
+impl Default for GridFormatOptions {
+    fn default() -> Self {
+        GridFormatOptions {
+            open: '[',
+            close: ']',
+            empty: ' ',
+            cell_width: 1,
+        }
     }
+}
 
-    /// Creates a change operation to add a new node to the grid.
-    fn create_add_change(&mut self, node: Node) {
-        self.pending_changes
-            .push(Change::Add(AddChangeData { value: node }));
+/// Version of the [`GridSnapshot`] layout written by this crate.
+///
+/// Snapshots serialized before versioning was introduced deserialize with a
+/// `schema_version` of 0.
+pub const SNAPSHOT_SCHEMA_VERSION: u32 = 1;
+
+/// A checkpoint of the grid layout that can be restored later.
+///
+/// Created by [`GridEngine::snapshot`] and consumed by [`GridEngine::restore`].
+/// The layout and the engine configuration (expansion flags, collision
+/// strategy, add placement, item gap, bounds snapping and size hints) are
+/// captured, event listeners are not part of the snapshot.
+/// Snapshots compare equal when both layout and configuration match, so they
+/// can be used to assert layouts directly in tests.
+///
+/// With the `serde` feature enabled the snapshot can be serialized, fields
+/// missing from older payloads fall back to the engine defaults.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridSnapshot {
+    /// Version of the layout this snapshot was written with
+    #[cfg_attr(feature = "serde", serde(default))]
+    schema_version: u32,
+    /// The grid structure at the time of the snapshot
+    grid: InnerGrid,
+    /// The items at the time of the snapshot
+    items: BTreeMap<String, Node>,
+    /// The collision strategy at the time of the snapshot
+    #[cfg_attr(feature = "serde", serde(default))]
+    collision_strategy: CollisionStrategy,
+    /// The add placement at the time of the snapshot
+    #[cfg_attr(feature = "serde", serde(default))]
+    add_placement: AddPlacement,
+    /// The gap left by collision resolution at the time of the snapshot
+    #[cfg_attr(feature = "serde", serde(default))]
+    item_gap: usize,
+    /// Whether moves were snapped to the bounds at the time of the snapshot
+    #[cfg_attr(feature = "serde", serde(default))]
+    snap_to_bounds: bool,
+    /// The first row of the sticky footer at the time of the snapshot
+    #[cfg_attr(feature = "serde", serde(default))]
+    footer_row: Option<usize>,
+    /// The row height hints at the time of the snapshot
+    #[cfg_attr(feature = "serde", serde(default))]
+    row_heights: Vec<u32>,
+    /// The column width hints at the time of the snapshot
+    #[cfg_attr(feature = "serde", serde(default))]
+    col_widths: Vec<u32>,
+}
+
+impl GridSnapshot {
+    /// Returns the schema version this snapshot was written with.
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
     }
 
-    /// Get the node sorted by id
+    /// Returns whether the captured grid could expand vertically.
+    pub fn can_expand_y(&self) -> bool {
+        self.grid.can_expand_y()
+    }
+
+    /// Returns whether the captured grid could expand horizontally.
+    pub fn can_expand_x(&self) -> bool {
+        self.grid.can_expand_x()
+    }
+
+    /// Returns the captured collision strategy.
+    pub fn collision_strategy(&self) -> CollisionStrategy {
+        self.collision_strategy
+    }
+
+    /// Returns the captured add placement.
+    pub fn add_placement(&self) -> AddPlacement {
+        self.add_placement
+    }
+
+    /// Returns the captured gap left by collision resolution.
+    pub fn item_gap(&self) -> usize {
+        self.item_gap
+    }
+
+    /// Returns whether moves were snapped to the bounds.
+    pub fn snap_to_bounds(&self) -> bool {
+        self.snap_to_bounds
+    }
+
+    /// Returns the captured first row of the sticky footer.
+    pub fn footer_row(&self) -> Option<usize> {
+        self.footer_row
+    }
+
+    /// Returns the captured row height hints.
+    pub fn row_heights(&self) -> &[u32] {
+        &self.row_heights
+    }
+
+    /// Returns the captured column width hints.
+    pub fn col_widths(&self) -> &[u32] {
+        &self.col_widths
+    }
+
+    /// Counts the captured items by size.
+    ///
+    /// # Returns
+    ///
+    /// A map from each `(w, h)` found to the number of items of that size
     ///
     /// # Example
     ///
     /// ```
     /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
     ///
-    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let mut grid = GridEngine::new(10, 10);
-    /// grid.add_item("b".to_string(), 0, 0, 2, 2).unwrap();
-    /// grid.add_item("a".to_string(), 0, 2, 2, 2).unwrap();
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.add_item("box2", 2, 0, 2, 2)?;
     ///
-    /// let nodes = grid.get_nodes();
-    /// assert_eq!(nodes.len(), 2);
-    /// assert_eq!(nodes[0].id(), "a");
-    /// assert_eq!(nodes[1].id(), "b");
+    /// assert_eq!(grid.snapshot().size_histogram().get(&(2, 2)), Some(&2));
     /// # Ok(())
     /// # }
     /// ```
-    pub fn get_nodes(&self) -> Vec<&Node> {
-        let mut cloned: Vec<&Node> = self.items.values().collect();
-        // Would be better to sort by some created_at
-        cloned.sort_by_key(|n| n.id.clone());
-        cloned
+    pub fn size_histogram(&self) -> BTreeMap<(usize, usize), usize> {
+        let mut histogram = BTreeMap::new();
+        for node in self.items.values() {
+            *histogram.entry((node.w, node.h)).or_insert(0) += 1;
+        }
+        histogram
     }
 
-    /// Gets a reference to the underlying grid structure.
-    ///
-    /// This provides access to the raw grid data for inspection purposes.
-    /// Note that modifications should be made through GridEngine's public methods
-    /// rather than directly manipulating the inner grid.
-    ///
-    /// # Returns
+    /// Computes the changes needed to go from this snapshot to `other`.
     ///
-    /// A reference to the InnerGrid instance
+    /// Ids missing from `other` produce a `Remove`, new ids produce an `Add`,
+    /// ids whose size changed produce a `Resize` and ids that otherwise changed
+    /// produce a `Move`. Applying the result in order to this snapshot's layout
+    /// reproduces `other`.
     ///
     /// # Example
     ///
     /// ```
-    /// use grid_engine::grid_engine::GridEngine;
-    /// use std::error::Error;
+    /// use grid_engine::grid_engine::{Change, GridEngine};
+    /// # use std::error::Error;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// let grid = GridEngine::new(10, 10);
-    /// let inner_grid = grid.get_inner_grid();
-    /// assert_eq!(inner_grid.rows(), 10);
-    /// assert_eq!(inner_grid.cols(), 10);
+    /// let mut grid = GridEngine::new(10, 10);
+    /// let before = grid.snapshot();
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// let changes = before.diff(&grid.snapshot());
+    /// assert!(matches!(changes.as_slice(), [Change::Add(_)]));
     /// # Ok(())
     /// # }
-    pub fn get_inner_grid(&self) -> &InnerGrid {
-        &self.grid
+    /// ```
+    pub fn diff(&self, other: &GridSnapshot) -> Vec<Change> {
+        diff_items(&self.items, &other.items)
     }
 
-    /// Adds an item to the grid at the specified position.
-    ///
-    /// If the new item would collide with existing items, those items are
-    /// automatically repositioned to avoid overlap.
-    ///
-    /// # Arguments
-    ///
-    /// * `id` - Unique identifier for the item
-    /// * `x` - X coordinate (column) for item placement
-    /// * `y` - Y coordinate (row) for item placement
-    /// * `w` - Width of the item in grid cells
-    /// * `h` - Height of the item in grid cells
+    /// Serializes the layout into a canonical JSON string.
     ///
-    /// # Returns
-    ///
-    /// * `Ok(&Node)` - Reference to the newly added node
-    /// * `Err(GridEngineError)` - If item already exists or placement fails
+    /// The output only holds the column count and the items, sorted by id, with
+    /// their geometry in a fixed field order. The cell matrix and the current row
+    /// count are left out, so two logically equal layouts always produce
+    /// byte-identical output, regardless of how they were built.
     ///
     /// # Example
     ///
     /// ```
     /// use grid_engine::grid_engine::GridEngine;
-    /// use std::error::Error;
+    /// # use std::error::Error;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// let mut grid = GridEngine::new(10, 10);
-    /// grid.add_item("box1".to_string(), 0, 0, 2, 2)?; // 2x2 item at top-left
-    ///
-    /// // Check if the item was added correctly
-    /// let item = grid.get_nodes();
-    /// assert_eq!(item.len(), 1);
-    /// assert_eq!(item[0].id(), "box1");
+    /// let mut grid = GridEngine::new(4, 4);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
     ///
+    /// assert_eq!(
+    ///     grid.snapshot().to_canonical_json(),
+    ///     r#"{"cols":4,"items":[{"id":"box1","x":0,"y":0,"w":2,"h":2}]}"#
+    /// );
     /// # Ok(())
     /// # }
     /// ```
-    pub fn add_item(
-        &mut self,
-        id: impl Into<String>,
-        x: usize,
-        y: usize,
-        w: usize,
-        h: usize,
-    ) -> Result<&Node, GridEngineError> {
-        let id = id.into();
-        if self.items.contains_key(&id) {
-            return Err(GridEngineError::Item(ItemError::ItemAlreadyExists { id }));
+    pub fn to_canonical_json(&self) -> String {
+        let items = self
+            .items
+            .values()
+            .map(|node| {
+                format!(
+                    r#"{{"id":{},"x":{},"y":{},"w":{},"h":{}}}"#,
+                    json_string(&node.id),
+                    node.x,
+                    node.y,
+                    node.w,
+                    node.h
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+
+        format!(r#"{{"cols":{},"items":[{}]}}"#, self.grid.cols(), items)
+    }
+}
+
+/// Encodes a string as a quoted JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() + 2);
+    encoded.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => encoded.push_str("\\\""),
+            '\\' => encoded.push_str("\\\\"),
+            '\n' => encoded.push_str("\\n"),
+            '\r' => encoded.push_str("\\r"),
+            '\t' => encoded.push_str("\\t"),
+            c if (c as u32) < 0x20 => encoded.push_str(&format!("\\u{:04x}", c as u32)),
+            c => encoded.push(c),
+        }
+    }
+    encoded.push('"');
+    encoded
+}
+
+/// Collects the ids of the items occupying a footprint, in the order their
+/// cells are first found and without repeats.
+///
+/// The grid is never expanded: cells past its bounds that it could grow into
+/// count as empty, while cells it can't reach are an error.
+///
+/// # Arguments
+///
+/// * `grid` - Grid to look into
+/// * `exclude` - Id to leave out of the result, usually the item being placed
+/// * `position` - Top-left corner of the footprint
+/// * `size` - Dimensions of the footprint
+fn colliding_ids<'g>(
+    grid: &'g InnerGrid,
+    exclude: Option<&str>,
+    position: Position,
+    size: Size,
+) -> Result<Vec<&'g str>, InnerGridError> {
+    let mut ids: Vec<&str> = Vec::new();
+
+    for_cell(position, size, &mut |x, y| {
+        let cell = match grid.peek(x, y) {
+            Some(cell) => cell,
+            None if grid.can_reach(x, y) => None,
+            None => return Err(grid.out_of_bounds(x, y)),
         };
 
-        let node = self.new_node(id, x, y, w, h);
-        let node_id = node.id.to_string();
+        if let Some(id) = cell
+            && Some(id) != exclude
+            && !ids.contains(&id)
+        {
+            ids.push(id);
+        }
+        Ok(())
+    })?;
 
-        self.handle_collision(&node, x, y, &mut self.grid.clone())?;
+    Ok(ids)
+}
 
-        self.create_add_change(node);
+/// Finds the first free spot for a footprint, scanning rows top to bottom and
+/// columns left to right.
+///
+/// Rows the grid can grow into count as free, so the spot may lie past the
+/// current bounds.
+///
+/// # Returns
+///
+/// * `Ok(Position)` - The top-left corner of the first free spot
+/// * `Err(InnerGridError)` - If no spot is found before the row limit
+fn first_fit(grid: &InnerGrid, size: Size) -> Result<Position, InnerGridError> {
+    let max_x = grid.cols().saturating_sub(size.w);
 
-        self.apply_changes(&self.pending_changes.clone())?;
-        self.pending_changes.clear();
+    for y in 0.. {
+        let bottom = y + size.h - 1;
+        if !grid.can_reach(0, bottom) {
+            return Err(grid.out_of_bounds(0, bottom));
+        }
 
-        let node = self
-            .items
-            .get(&node_id)
-            .ok_or(InnerGridError::MismatchedGridItem { id: node_id })?;
-        Ok(node)
+        let spot = (0..=max_x).find(|&x| {
+            colliding_ids(grid, None, Position::new(x, y), size).is_ok_and(|ids| ids.is_empty())
+        });
+        if let Some(x) = spot {
+            return Ok(Position::new(x, y));
+        }
     }
 
-    fn create_remove_change(&mut self, node: &Node) {
-        self.pending_changes.push(Change::Remove(RemoveChangeData {
-            value: node.clone(),
-        }));
+    unreachable!("rows can't grow past usize::MAX")
+}
+
+/// Encodes a string as a CSV field, quoting it only when needed.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
     }
+}
 
-    /// Removes an item from the grid by its ID.
+/// Computes the changes needed to go from the `old` items to the `new` items.
+///
+/// Removals come first, followed by moves and then additions, so the result can
+/// be applied in order without transient collisions.
+fn diff_items(old: &BTreeMap<String, Node>, new: &BTreeMap<String, Node>) -> Vec<Change> {
+    let mut removed = Vec::new();
+    let mut moved = Vec::new();
+    let mut added = Vec::new();
+
+    for (id, old_node) in old {
+        match new.get(id) {
+            Some(new_node) if new_node.w != old_node.w || new_node.h != old_node.h => moved.push(
+                Change::Resize(ResizeChangeData::new(old_node.clone(), new_node.clone())),
+            ),
+            Some(new_node) if new_node.x != old_node.x || new_node.y != old_node.y => moved.push(
+                Change::Move(MoveChangeData::new(old_node.clone(), new_node.clone())),
+            ),
+            Some(new_node) if new_node != old_node => moved.push(Change::Reorder(
+                ReorderChangeData::new(old_node.clone(), new_node.clone()),
+            )),
+            Some(_) => {}
+            None => removed.push(Change::Remove(RemoveChangeData::new(old_node.clone()))),
+        }
+    }
+
+    for (id, new_node) in new {
+        if !old.contains_key(id) {
+            added.push(Change::Add(AddChangeData::new(new_node.clone())));
+        }
+    }
+
+    removed.into_iter().chain(moved).chain(added).collect()
+}
+
+/// Applies a batch of changes to a grid and its items map, in order.
+///
+/// Stops at the first failure, leaving the grid and items partially updated,
+/// so callers wanting atomicity should work on copies.
+fn apply_changes_to(
+    grid: &mut InnerGrid,
+    items: &mut BTreeMap<String, Node>,
+    changes: &[Change],
+) -> Result<(), InnerGridError> {
+    for change in changes.iter() {
+        match &change {
+            Change::Add(data) => {
+                let node = &data.value;
+
+                node.update_grid(grid, UpdateGridOperation::Add)?;
+
+                items.insert(node.id.to_string(), node.clone());
+            }
+            Change::Remove(data) => {
+                let node = &data.value;
+
+                node.update_grid(grid, UpdateGridOperation::Remove)?;
+
+                items.remove(&node.id);
+            }
+            Change::Move(MoveChangeData {
+                old_value: old_node,
+                new_value: node,
+            })
+            | Change::Resize(ResizeChangeData {
+                old_value: old_node,
+                new_value: node,
+            })
+            | Change::Reorder(ReorderChangeData {
+                old_value: old_node,
+                new_value: node,
+            }) => {
+                old_node.update_grid(grid, UpdateGridOperation::Remove)?;
+
+                items.insert(node.id.to_string(), node.clone());
+
+                node.update_grid(grid, UpdateGridOperation::Add)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrites the items' cells so each one shows its topmost item.
+///
+/// Items are painted in creation order, so later ones end up on top. Used
+/// once items are allowed to overlap, where removing or moving the top item
+/// would otherwise leave holes over the items below it.
+///
+/// # Returns
+///
+/// The ids buried under each overlapped cell's topmost item, bottom first
+fn restack(
+    grid: &mut InnerGrid,
+    items: &BTreeMap<String, Node>,
+) -> Result<BTreeMap<(usize, usize), Vec<String>>, InnerGridError> {
+    let mut nodes: Vec<&Node> = items.values().collect();
+    nodes.sort_by_key(|node| (node.seq, node.id.clone()));
+
+    for node in &nodes {
+        node.for_cell(&mut |x, y| grid.set(x, y, None))?;
+    }
+
+    let mut stacked: BTreeMap<(usize, usize), Vec<String>> = BTreeMap::new();
+    for node in nodes {
+        node.for_cell(&mut |x, y| {
+            let below = grid.get(x, y).flatten().map(str::to_string);
+            grid.set(x, y, Some(&node.id))?;
+            if let Some(below) = below {
+                stacked.entry((x, y)).or_default().push(below);
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(stacked)
+}
+
+/// The main engine for managing a 2D grid system.
+///
+/// `GridEngine` provides functionality for:
+/// - Adding items to specific grid positions
+/// - Moving items while handling collisions
+/// - Removing items from the grid
+/// - Tracking changes through an event system
+/// - Expand the grid dynamically on the y axis
+///
+/// When items collide during placement or movement, the engine automatically
+/// repositions affected items to prevent overlapping, the default is to move the collided items down, increasing their y axis.
+#[derive(Debug)]
+pub struct GridEngine {
+    /// The underlying grid structure
+    grid: InnerGrid,
+    /// Map of item IDs to their Node representations
+    items: BTreeMap<String, Node>,
+    /// Changes waiting to be applied
+    pending_changes: Vec<Change>,
+    /// Event system for tracking grid changes
+    events: GridEvents,
+    /// Strategy used to resolve collisions
+    collision_strategy: CollisionStrategy,
+    /// Where added items go when their position is occupied
+    add_placement: AddPlacement,
+    /// Empty cells left between items displaced by collision resolution
+    item_gap: usize,
+    /// Whether move targets are clamped to keep items within the bounds
+    snap_to_bounds: bool,
+    /// First row of the sticky footer, whose items are never pushed down
+    footer_row: Option<usize>,
+    /// Minimum number of rows kept when trimming the grid
+    min_rows: usize,
+    /// Sequence number given to the next added item
+    next_seq: u64,
+    /// Whether mutations are rejected
+    frozen: bool,
+    /// Rendering height hint of each row, uniform when empty
+    row_heights: Vec<u32>,
+    /// Rendering width hint of each column, uniform when empty
+    col_widths: Vec<u32>,
+    /// Ids of the items buried under each overlapped cell's topmost item,
+    /// bottom first, see [`CollisionStrategy::Stack`]
+    stacked: BTreeMap<(usize, usize), Vec<String>>,
+}
+
+/// Clones the layout and configuration of the engine.
+///
+/// Event listeners are NOT cloned, since they are boxed closures. The clone
+/// starts with an empty event system, so changes made to it are never reported
+/// to the listeners registered on the original engine.
+impl Clone for GridEngine {
+    fn clone(&self) -> Self {
+        GridEngine {
+            grid: self.grid.clone(),
+            items: self.items.clone(),
+            pending_changes: self.pending_changes.clone(),
+            events: GridEvents::default(),
+            collision_strategy: self.collision_strategy,
+            add_placement: self.add_placement,
+            item_gap: self.item_gap,
+            snap_to_bounds: self.snap_to_bounds,
+            footer_row: self.footer_row,
+            min_rows: self.min_rows,
+            next_seq: self.next_seq,
+            frozen: self.frozen,
+            row_heights: self.row_heights.clone(),
+            col_widths: self.col_widths.clone(),
+            stacked: self.stacked.clone(),
+        }
+    }
+}
+
+impl GridEngine {
+    /// Creates a new GridEngine with specified dimensions.
     ///
     /// # Arguments
     ///
-    /// * `id` - ID of the item to remove
+    /// * `rows` - Initial number of rows in the grid
+    /// * `cols` - Initial number of columns in the grid
     ///
-    /// # Returns
+    /// # Panics
     ///
-    /// * `Ok(Node)` - The removed node
-    /// * `Err(GridEngineError)` - If item doesn't exist
+    /// Panics if `cols` is 0, since no item could ever be placed. Use
+    /// [`GridEngine::try_new_with_options`] for dimensions that aren't known
+    /// to be valid.
     ///
     /// # Example
     ///
     /// ```
     /// use grid_engine::grid_engine::GridEngine;
-    /// use std::error::Error;
     ///
-    /// # fn main() -> Result<(), Box<dyn Error>> {
-    ///
-    /// let mut grid = GridEngine::new(10, 10);
-    /// grid.add_item("box1".to_string(), 0, 0, 2, 2)?;
-    /// grid.remove_item("box1")?; // Removes the item
-    ///
-    /// # Ok(())
-    /// # }
+    /// let grid = GridEngine::new(10, 10); // Creates a 10x10 grid
     /// ```
-    pub fn remove_item(&mut self, id: &str) -> Result<Node, GridEngineError> {
-        let node = match self.items.get(id) {
-            Some(node) => node,
-            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
-                id: id.to_string(),
-            }))?,
-        }
-        .clone();
-
-        self.create_remove_change(&node);
-
-        self.apply_changes(&self.pending_changes.clone())?;
-        self.pending_changes.clear();
-        Ok(node)
+    pub fn new(rows: usize, cols: usize) -> GridEngine {
+        Self::new_with_options(GridOptions::new(rows, cols))
     }
 
-    /// Checks if a node would collide with any existing items at the specified position.
+    /// Creates a new GridEngine with as many rows as columns.
     ///
-    /// This is used internally to detect potential collisions before making grid changes.
-    /// It considers the node's dimensions and any existing items in the target area.
+    /// # Arguments
     ///
-    /// # Returns
+    /// * `size` - Initial number of rows and columns
     ///
-    /// * `Ok(Vec<&Node>)` - List of nodes that would collide with the given node
-    /// * `Err(InnerGridError)` - If position check fails (e.g., out of bounds)
-    fn will_collides_with(
-        &self,
-        node: &Node,
-        x: usize,
-        y: usize,
-        grid: &mut InnerGrid,
-    ) -> Result<Vec<&Node>, InnerGridError> {
-        let mut collides_with: Vec<&Node> = Vec::new();
-
-        for_cell(
-            ForCellArgs {
-                x,
-                y,
-                w: node.w,
-                h: node.h,
-            },
-            &mut |x, y| {
-                let cell = grid
-                    .get(x, y)
-                    .ok_or(InnerGridError::OutOfBoundsAccess { x, y })?;
-
-                match cell {
-                    Some(cell_ref) => {
-                        if cell_ref != &node.id {
-                            let node = self.items.get(cell_ref).ok_or(
-                                InnerGridError::MismatchedGridItem {
-                                    id: cell_ref.to_string(),
-                                },
-                            )?;
-
-                            if !collides_with.contains(&node) {
-                                collides_with.push(node);
-                            }
-                        }
-                    }
-                    None => {
-                        // Nothing to collide with
-                    }
-                }
-                Ok(())
-            },
-        )?;
-
-        Ok(collides_with)
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    ///
+    /// let grid = GridEngine::new_square(8);
+    /// assert_eq!(grid.get_inner_grid().rows(), 8);
+    /// assert_eq!(grid.get_inner_grid().cols(), 8);
+    /// ```
+    pub fn new_square(size: usize) -> GridEngine {
+        Self::new(size, size)
     }
 
-    /// Handles collision resolution when adding or moving items.
+    /// Creates a new GridEngine with every setting given up front.
     ///
-    /// When a collision is detected, this method:
-    /// 1. Identifies all affected items
-    /// 2. Calculates new positions for colliding items
-    /// 3. Creates appropriate move changes to relocate affected items
+    /// # Arguments
     ///
-    /// The default collision resolution strategy moves affected items downward,
-    /// which may trigger dynamic grid expansion in the y-axis.
-    fn handle_collision(
-        &mut self,
-        node: &Node,
-        x: usize,
-        y: usize,
-        grid: &mut InnerGrid,
-    ) -> Result<(), InnerGridError> {
-        let collides_with = self
-            .will_collides_with(node, x, y, grid)?
-            .iter()
-            .map(|n| (*n).clone())
-            .collect::<Vec<Node>>();
-
-        for collided in collides_with {
-            let mut new_grid = grid.clone();
-
-            node.update_grid(&mut new_grid, UpdateGridOperation::Remove)?;
-            let new_x = collided.x;
-            let new_y = y + node.h;
-            self.create_move_change(collided, new_x, new_y, &mut new_grid)?;
-        }
-
-        Ok(())
+    /// * `options` - Dimensions and configuration of the grid
+    ///
+    /// # Panics
+    ///
+    /// Panics if `options.cols` is 0, since no item could ever be placed. Use
+    /// [`GridEngine::try_new_with_options`] to get an error instead.
+    pub fn new_with_options(options: GridOptions) -> GridEngine {
+        Self::try_new_with_options(options).expect("GridEngine requires at least one column")
     }
 
-    /// Creates a change operation to move a node to a new position.
-    ///
-    /// This method:
-    /// 1. Handles any collisions at the new position
-    /// 2. Checks if the node was already scheduled to move
-    /// 3. Creates a Move change operation if needed
+    /// Creates a new GridEngine with every setting given up front, checking
+    /// the dimensions first.
     ///
     /// # Arguments
     ///
-    /// * `node` - The node to move
-    /// * `new_x` - Target x coordinate
-    /// * `new_y` - Target y coordinate
-    /// * `grid` - The grid to check for collisions
-    fn create_move_change(
-        &mut self,
-        node: Node,
-        new_x: usize,
-        new_y: usize,
-        grid: &mut InnerGrid,
-    ) -> Result<(), InnerGridError> {
-        let old_node = node.clone();
-        self.handle_collision(&node, new_x, new_y, grid)?;
-
-        let already_moved = self.pending_changes.iter().any(|change| match change {
-            Change::Move(data) => data.new_value.id == node.id,
-            _ => false,
-        });
-
-        if already_moved {
-            return Ok(());
+    /// * `options` - Dimensions and configuration of the grid
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(GridEngine)` - The new engine
+    /// * `Err(GridEngineError::NoColumns)` - If `options.cols` is 0
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::{GridEngine, GridOptions};
+    ///
+    /// assert!(GridEngine::try_new_with_options(GridOptions::new(4, 0)).is_err());
+    ///
+    /// let grid = GridEngine::try_new_with_options(GridOptions::new(4, 6))?;
+    /// assert_eq!(grid.get_inner_grid().cols(), 6);
+    /// # Ok::<(), grid_engine::error::GridEngineError>(())
+    /// ```
+    pub fn try_new_with_options(options: GridOptions) -> Result<GridEngine, GridEngineError> {
+        if options.cols == 0 {
+            return Err(GridEngineError::NoColumns {
+                rows: options.rows,
+                cols: options.cols,
+            });
         }
 
-        self.pending_changes.push(Change::Move(MoveChangeData {
-            old_value: old_node,
-            new_value: Node::new(node.id.to_string(), new_x, new_y, node.w, node.h),
-        }));
+        let mut grid = InnerGrid::new(options.rows, options.cols);
+        grid.set_can_expand_x(options.can_expand_x);
+        grid.set_can_expand_y(options.can_expand_y);
+        grid.set_max_rows(options.max_rows);
 
-        Ok(())
+        Ok(GridEngine {
+            grid,
+            items: BTreeMap::new(),
+            pending_changes: Vec::new(),
+            events: GridEvents::default(),
+            collision_strategy: options.collision_strategy,
+            add_placement: options.add_placement,
+            item_gap: 0,
+            snap_to_bounds: false,
+            footer_row: None,
+            min_rows: options.rows,
+            next_seq: 0,
+            frozen: false,
+            row_heights: Vec::new(),
+            col_widths: Vec::new(),
+            stacked: BTreeMap::new(),
+        })
     }
 
-    /// Moves an existing item to a new position in the grid.
+    /// Creates a new GridEngine from a dense grid of cell ids.
     ///
-    /// If the move would cause collisions, affected items are automatically
-    /// repositioned to prevent overlap.
+    /// Every cell holding the same id belongs to one item, whose position and
+    /// size are inferred from the cells. The grid takes the dimensions of the
+    /// dense grid, with shorter rows padded with empty cells.
     ///
     /// # Arguments
     ///
-    /// * `id` - ID of the item to move
-    /// * `new_x` - New X coordinate
-    /// * `new_y` - New Y coordinate
+    /// * `dense` - The cells, row by row
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If move successful
-    /// * `Err(GridEngineError)` - If item doesn't exist or move invalid
+    /// * `Ok(GridEngine)` - The engine holding the inferred items
+    /// * `Err(GridEngineError)` - If `dense` has no columns or an item's cells
+    ///   don't form a solid rectangle
     ///
     /// # Example
     ///
     /// ```
     /// use grid_engine::grid_engine::GridEngine;
-    /// # use std::error::Error;
-    ///
-    /// # fn main() -> Result<(), Box<dyn Error>> {
-    ///
-    /// let mut grid = GridEngine::new(10, 10);
-    /// grid.add_item("box1".to_string(), 0, 0, 2, 2)?;
-    /// grid.move_item("box1", 2, 2)?; // Moves box to position 2,2
     ///
-    /// // Check if the item was moved correctly
-    /// let item = grid.get_nodes();
-    /// assert_eq!(item.len(), 1);
-    /// assert_eq!(item[0].x(), &2);
-    /// assert_eq!(item[0].y(), &2);
+    /// let a = Some("a".to_string());
+    /// let grid = GridEngine::from_dense(vec![
+    ///     vec![None, a.clone(), a.clone()],
+    ///     vec![None, a.clone(), a.clone()],
+    /// ])?;
     ///
-    /// # Ok(())
+    /// let item = grid.get_nodes()[0].clone();
+    /// assert_eq!((*item.x(), *item.y(), *item.w(), *item.h()), (1, 0, 2, 2));
+    /// # Ok::<(), grid_engine::error::GridEngineError>(())
+    /// ```
+    pub fn from_dense(dense: Vec<Vec<Option<String>>>) -> Result<GridEngine, GridEngineError> {
+        let cols = dense.iter().map(Vec::len).max().unwrap_or(0);
+        let mut engine = Self::try_new_with_options(GridOptions::new(dense.len(), cols))?;
+
+        // Bounds and cell count of each id, in order of first appearance
+        let mut regions: Vec<(String, usize, usize, usize, usize, usize)> = Vec::new();
+        for (y, row) in dense.iter().enumerate() {
+            for (x, id) in row.iter().enumerate() {
+                let Some(id) = id else {
+                    continue;
+                };
+
+                match regions.iter_mut().find(|region| &region.0 == id) {
+                    Some((_, min_x, min_y, max_x, max_y, count)) => {
+                        *min_x = (*min_x).min(x);
+                        *min_y = (*min_y).min(y);
+                        *max_x = (*max_x).max(x);
+                        *max_y = (*max_y).max(y);
+                        *count += 1;
+                    }
+                    None => regions.push((id.clone(), x, y, x, y, 1)),
+                }
+            }
+        }
+
+        for (id, min_x, min_y, max_x, max_y, count) in regions {
+            let (w, h) = (max_x - min_x + 1, max_y - min_y + 1);
+            if count != w * h {
+                return Err(ItemError::NotRectangular { id }.into());
+            }
+
+            let node = engine.new_node(id, min_x, min_y, w, h);
+            node.update_grid(&mut engine.grid, UpdateGridOperation::Add)?;
+            engine.items.insert(node.id.clone(), node);
+        }
+
+        Ok(engine)
+    }
+
+    /// Returns the strategy currently used to resolve collisions.
+    pub fn collision_strategy(&self) -> CollisionStrategy {
+        self.collision_strategy
+    }
+
+    /// Sets the strategy used to resolve collisions on subsequent operations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::{CollisionStrategy, GridEngine};
+    ///
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.set_collision_strategy(CollisionStrategy::Swap);
+    /// assert_eq!(grid.collision_strategy(), CollisionStrategy::Swap);
+    /// ```
+    pub fn set_collision_strategy(&mut self, strategy: CollisionStrategy) {
+        self.collision_strategy = strategy;
+    }
+
+    /// Returns where added items currently go when their position is occupied.
+    pub fn add_placement(&self) -> AddPlacement {
+        self.add_placement
+    }
+
+    /// Sets where items added on subsequent operations go when their position is occupied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::{AddPlacement, GridEngine};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.set_add_placement(AddPlacement::FindNextFree);
+    ///
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.add_item("box2", 0, 0, 2, 2)?;
+    /// assert_eq!(grid.get_nodes()[0].y(), &0);
+    /// assert_eq!(grid.get_nodes()[1].y(), &2);
+    /// # Ok(())
     /// # }
+    /// ```
+    pub fn set_add_placement(&mut self, placement: AddPlacement) {
+        self.add_placement = placement;
+    }
+
+    /// Returns the number of empty cells left between displaced items.
+    pub fn item_gap(&self) -> usize {
+        self.item_gap
+    }
+
+    /// Sets the number of empty cells left between items on subsequent operations.
+    ///
+    /// Collision resolution normally leaves displaced items flush against the
+    /// item that pushed them. With a gap they are pushed that many cells
+    /// further, the incoming item settles that far below items it can't
+    /// displace and [`GridEngine::compact_horizontal`] stops that far from its
+    /// neighbours. Items placed where requested, without colliding, are left
+    /// as they are. Defaults to 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `gap` - Number of empty cells to leave between items
+    ///
+    /// # Example
     ///
     /// ```
-    pub fn move_item(
-        &mut self,
-        id: &str,
-        new_x: usize,
-        new_y: usize,
-    ) -> Result<(), GridEngineError> {
-        let node = match self.items.get(id) {
-            Some(node) => node,
-            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
-                id: id.to_string(),
-            }))?,
-        };
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.set_item_gap(1);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.add_item("box2", 0, 0, 2, 2)?;
+    ///
+    /// // box1 is pushed below box2, one row apart
+    /// assert_eq!(grid.get_nodes()[0].y(), &3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_item_gap(&mut self, gap: usize) {
+        self.item_gap = gap;
+    }
 
-        self.create_move_change(node.clone(), new_x, new_y, &mut self.grid.clone())?;
+    /// Returns whether move targets are clamped to keep items within the bounds.
+    pub fn snap_to_bounds(&self) -> bool {
+        self.snap_to_bounds
+    }
 
-        self.apply_changes(&self.pending_changes.clone())?;
-        self.pending_changes.clear();
+    /// Sets whether move targets are clamped to keep items within the bounds.
+    ///
+    /// Meant for dragging near the edges: instead of failing, or growing the
+    /// grid, a move that would leave the item partly off the grid is snapped
+    /// back so the item stays fully visible. The x coordinate is clamped to
+    /// the columns unless the grid expands horizontally, and the y coordinate
+    /// to the rows when the grid can't expand vertically, or to the row limit
+    /// when one is set. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `snap` - Whether to snap move targets
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 3, 1)?;
+    /// assert!(grid.move_item("box1", 8, 0).is_err());
+    ///
+    /// grid.set_snap_to_bounds(true);
+    /// grid.move_item("box1", 8, 0)?;
+    /// assert_eq!(grid.get_nodes()[0].x(), &7);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_snap_to_bounds(&mut self, snap: bool) {
+        self.snap_to_bounds = snap;
+    }
+
+    /// Returns the first row of the sticky footer, if any.
+    pub fn footer_row(&self) -> Option<usize> {
+        self.footer_row
+    }
+
+    /// Sets the first row of the sticky footer.
+    ///
+    /// Items starting at or below this row are never pushed further down by
+    /// collision resolution, which would make them fall off the footer.
+    /// Instead they are pushed up, right above the incoming item, and the
+    /// operation fails with [`InnerGridError::FooterPushBlocked`] when there
+    /// is no free room there. Pushes in other directions are unaffected.
+    /// Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The first row of the footer, `None` to disable it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(6, 4);
+    /// grid.set_footer_row(Some(4));
+    /// grid.add_item("footer", 0, 4, 4, 2)?;
+    /// grid.add_item("box1", 0, 4, 2, 1)?;
+    ///
+    /// // The footer item made room above instead of falling off
+    /// assert_eq!(grid.get_nodes()[1].y(), &2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_footer_row(&mut self, row: Option<usize>) {
+        self.footer_row = row;
+    }
+
+    /// Creates a new node with the specified parameters and the next sequence number.
+    fn new_node(&mut self, id: impl Into<String>, x: usize, y: usize, w: usize, h: usize) -> Node {
+        let node = Node {
+            seq: self.next_seq,
+            ..Node::new(id.into(), x, y, w, h)
+        };
+        self.next_seq += 1;
+        node
+    }
+
+    /// Checks that an item of width `w` fits within the grid columns.
+    ///
+    /// Always passes when the grid can expand horizontally.
+    fn check_fits_width(&self, w: usize) -> Result<(), ItemError> {
+        let cols = self.grid.cols();
+        if w > cols && !self.grid.can_expand_x() {
+            return Err(ItemError::TooWide { w, cols });
+        }
 
         Ok(())
     }
 
-    /// Applies a batch of changes to the grid.
+    /// Checks that an item of size `w` x `h` has some area and fits within the
+    /// grid columns.
+    fn check_dimensions(&self, w: usize, h: usize) -> Result<(), ItemError> {
+        if w == 0 || h == 0 {
+            return Err(ItemError::InvalidDimensions { w, h });
+        }
+
+        self.check_fits_width(w)
+    }
+
+    /// Runs a multi-step operation on a copy of the engine, then applies the
+    /// net result at once.
     ///
-    /// This method handles the actual application of all pending changes to both
-    /// the grid structure and the item tracking system. Changes are applied in order,
-    /// and all operations are executed atomically - if any change fails, none of
-    /// the changes will be applied.
+    /// A failure halfway leaves this engine untouched, and listeners get a
+    /// single event holding the net changes rather than one per step. No event
+    /// is sent when nothing changed.
     ///
-    /// After successful application, triggers change events to notify any registered listeners.
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Change>)` - The changes applied
+    /// * `Err(GridEngineError)` - If `f` or applying its result fails, in which
+    ///   case nothing is changed
+    fn apply_on_working_copy(
+        &mut self,
+        f: impl FnOnce(&mut GridEngine) -> Result<(), GridEngineError>,
+    ) -> Result<Vec<Change>, GridEngineError> {
+        let mut working = self.clone();
+        f(&mut working)?;
+
+        let changes = diff_items(&self.items, &working.items);
+        if !changes.is_empty() {
+            self.apply_changes(&changes)?;
+        }
+
+        Ok(changes)
+    }
+
+    /// Creates a change operation to add a new node to the grid.
+    fn create_add_change(&mut self, node: Node) {
+        self.pending_changes
+            .push(Change::Add(AddChangeData { value: node }));
+    }
+
+    /// Get the node sorted by id
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("b".to_string(), 0, 0, 2, 2).unwrap();
+    /// grid.add_item("a".to_string(), 0, 2, 2, 2).unwrap();
+    ///
+    /// let nodes = grid.get_nodes();
+    /// assert_eq!(nodes.len(), 2);
+    /// assert_eq!(nodes[0].id(), "a");
+    /// assert_eq!(nodes[1].id(), "b");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_nodes(&self) -> Vec<&Node> {
+        let mut cloned: Vec<&Node> = self.items.values().collect();
+        cloned.sort_by_key(|n| n.id.clone());
+        cloned
+    }
+
+    /// Get the nodes sorted by creation order
+    ///
+    /// Items added later come last, which is the order to draw them in so
+    /// newer items are rendered on top.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("b".to_string(), 0, 0, 2, 2)?;
+    /// grid.add_item("a".to_string(), 0, 2, 2, 2)?;
+    ///
+    /// let nodes = grid.get_nodes_by_creation();
+    /// assert_eq!(nodes[0].id(), "b");
+    /// assert_eq!(nodes[1].id(), "a");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_nodes_by_creation(&self) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = self.items.values().collect();
+        nodes.sort_by_key(|n| (n.seq, n.id.clone()));
+        nodes
+    }
+
+    /// Returns the number of items in the grid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// assert!(grid.is_empty());
+    ///
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// assert_eq!(grid.total_items(), 1);
+    /// assert!(!grid.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn total_items(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns whether the grid holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Get the nodes sorted with a custom comparator
+    ///
+    /// The sort is stable and starts from the id order, so nodes comparing
+    /// equal stay sorted by id.
     ///
     /// # Arguments
     ///
-    /// * `changes` - Vector of changes to apply (Add, Remove, or Move operations)
+    /// * `cmp` - Comparator deciding the order of two nodes
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("a".to_string(), 4, 2, 2, 2)?;
+    /// grid.add_item("b".to_string(), 0, 0, 2, 2)?;
+    ///
+    /// // Reading order, top to bottom then left to right
+    /// let nodes = grid.items_sorted_by(|a, b| (a.y(), a.x()).cmp(&(b.y(), b.x())));
+    /// assert_eq!(nodes[0].id(), "b");
+    /// assert_eq!(nodes[1].id(), "a");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn items_sorted_by<F>(&self, cmp: F) -> Vec<Node>
+    where
+        F: FnMut(&Node, &Node) -> Ordering,
+    {
+        let mut nodes: Vec<Node> = self.items.values().cloned().collect();
+        nodes.sort_by(cmp);
+        nodes
+    }
+
+    /// Finds the first node matching a predicate, in id order
+    ///
+    /// # Arguments
+    ///
+    /// * `pred` - Predicate the node must satisfy
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("a".to_string(), 0, 0, 2, 2)?;
+    /// grid.add_item("b".to_string(), 4, 4, 1, 1)?;
+    ///
+    /// let node = grid.find_item_by(|node| node.x() >= &4);
+    /// assert_eq!(node.map(|node| node.id()), Some("b"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_item_by<F>(&self, pred: F) -> Option<&Node>
+    where
+        F: Fn(&Node) -> bool,
+    {
+        self.items.values().find(|node| pred(node))
+    }
+
+    /// Moves an item to the end of the creation order, so it's drawn on top.
+    ///
+    /// The item keeps its position, only its creation sequence number changes.
+    /// Listeners are notified with a single [`Change::Reorder`], nothing is
+    /// notified if the item is already last.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to bring to front
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If all changes were applied successfully
-    /// * `Err(GridEngineError)` - If any change application fails
+    /// * `Ok(())` - If the item was reordered, or already last
+    /// * `Err(GridEngineError)` - If the item doesn't exist
+    ///
+    /// # Example
+    ///
     /// ```
-    fn apply_changes(&mut self, changes: &[Change]) -> Result<(), GridEngineError> {
-        for change in changes.iter() {
-            match &change {
-                Change::Add(data) => {
-                    let node = &data.value;
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("a", 0, 0, 2, 2)?;
+    /// grid.add_item("b", 2, 0, 2, 2)?;
+    ///
+    /// grid.bring_to_front("a")?;
+    /// assert_eq!(grid.get_nodes_by_creation()[1].id(), "a");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bring_to_front(&mut self, id: &str) -> Result<(), GridEngineError> {
+        let node = match self.items.get(id) {
+            Some(node) => node.clone(),
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: id.to_string(),
+            }))?,
+        };
+
+        if self.get_nodes_by_creation().last().map(|last| &last.id) == Some(&node.id) {
+            return Ok(());
+        }
+
+        let reordered = Node {
+            seq: self.next_seq,
+            ..node.clone()
+        };
+        self.pending_changes
+            .push(Change::Reorder(ReorderChangeData::new(node, reordered)));
+
+        self.apply_changes(&self.pending_changes.clone())?;
+        self.pending_changes.clear();
+
+        Ok(())
+    }
+
+    /// Moves an item to the start of the creation order, so it's drawn below
+    /// every other item.
+    ///
+    /// The item keeps its position, only its creation sequence number changes.
+    /// Listeners are notified with a single event of [`Change::Reorder`]s, which
+    /// also shifts the other items when there's no lower number left to give.
+    /// Nothing is notified if the item is already first.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to send to back
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the item was reordered, or already first
+    /// * `Err(GridEngineError)` - If the item doesn't exist
+    pub fn send_to_back(&mut self, id: &str) -> Result<(), GridEngineError> {
+        let node = match self.items.get(id) {
+            Some(node) => node.clone(),
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: id.to_string(),
+            }))?,
+        };
+
+        let first_seq = match self.get_nodes_by_creation().first() {
+            Some(first) if first.id == node.id => return Ok(()),
+            Some(first) => first.seq,
+            None => return Ok(()),
+        };
+
+        if first_seq == 0 {
+            let others: Vec<Node> = self
+                .items
+                .values()
+                .filter(|other| other.id != node.id)
+                .cloned()
+                .collect();
+            for other in others {
+                let shifted = Node {
+                    seq: other.seq + 1,
+                    ..other.clone()
+                };
+                self.pending_changes
+                    .push(Change::Reorder(ReorderChangeData::new(other, shifted)));
+            }
+        }
+
+        let reordered = Node {
+            seq: first_seq.saturating_sub(1),
+            ..node.clone()
+        };
+        self.pending_changes
+            .push(Change::Reorder(ReorderChangeData::new(node, reordered)));
+
+        self.apply_changes(&self.pending_changes.clone())?;
+        self.pending_changes.clear();
+
+        Ok(())
+    }
+
+    /// Gets a reference to the underlying grid structure.
+    ///
+    /// This provides access to the raw grid data for inspection purposes.
+    /// Note that modifications should be made through GridEngine's public methods
+    /// rather than directly manipulating the inner grid.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the InnerGrid instance
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let grid = GridEngine::new(10, 10);
+    /// let inner_grid = grid.get_inner_grid();
+    /// assert_eq!(inner_grid.rows(), 10);
+    /// assert_eq!(inner_grid.cols(), 10);
+    /// # Ok(())
+    /// # }
+    pub fn get_inner_grid(&self) -> &InnerGrid {
+        &self.grid
+    }
+
+    /// Returns the changes queued for the operation being built.
+    ///
+    /// Every public operation applies and clears its changes before returning,
+    /// so this is empty between calls. It is meant for debugging the changes an
+    /// operation accumulates before they are committed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// assert!(grid.peek_pending().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn peek_pending(&self) -> &[Change] {
+        &self.pending_changes
+    }
+
+    /// Materializes the grid as a dense matrix of cells.
+    ///
+    /// The result is in row-major order, so it is indexed as `dense[y][x]`.
+    /// Each cell holds a copy of the id of the item occupying it, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(3, 4);
+    /// grid.add_item("box1", 1, 0, 2, 1)?;
+    ///
+    /// let dense = grid.to_dense();
+    /// assert_eq!(dense.len(), 3);
+    /// assert_eq!(dense[0][1], Some("box1".to_string()));
+    /// assert_eq!(dense[1][1], None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_dense(&self) -> Vec<Vec<Option<String>>> {
+        map_indices(0..self.grid.rows(), |y| {
+            self.grid
+                .iter_row_ids(y)
+                .map(|cell| cell.map(str::to_string))
+                .collect()
+        })
+    }
+
+    /// Exports the items as CSV, one line per item sorted by id.
+    ///
+    /// The first line is the `id,x,y,w,h` header. Ids holding commas, quotes or
+    /// line breaks are quoted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 2, 1, 3, 2)?;
+    ///
+    /// assert_eq!(grid.export_csv(), "id,x,y,w,h\nbox1,2,1,3,2\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_csv(&self) -> String {
+        let mut csv = String::from("id,x,y,w,h\n");
+        for node in self.items.values() {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                csv_field(&node.id),
+                node.x,
+                node.y,
+                node.w,
+                node.h
+            ));
+        }
+
+        csv
+    }
+
+    /// Exports the cells as CSV, one line per row and one field per column.
+    ///
+    /// Occupied cells hold the id of their item, empty cells are left empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(2, 3);
+    /// grid.add_item("a", 1, 0, 2, 1)?;
+    ///
+    /// assert_eq!(grid.export_csv_grid(), ",a,a\n,,\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_csv_grid(&self) -> String {
+        let mut csv = String::new();
+        for y in 0..self.grid.rows() {
+            let fields: Vec<String> = (self.grid.iter_row_ids(y))
+                .map(|cell| cell.map(csv_field).unwrap_or_default())
+                .collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Renders the grid as text, one line per row, with the default options.
+    ///
+    /// Each cell is rendered as `[id]`, padded to `cell_width` characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `cell_width` - Minimum width of each cell's content
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(2, 3);
+    /// grid.add_item("a", 0, 0, 2, 1)?;
+    ///
+    /// assert_eq!(grid.get_grid_formatted(1), "[a][a][ ]\n[ ][ ][ ]\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_grid_formatted(&self, cell_width: usize) -> String {
+        self.get_grid_formatted_with(GridFormatOptions {
+            cell_width,
+            ..GridFormatOptions::default()
+        })
+    }
+
+    /// Renders the grid as text, one line per row, with custom options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Delimiters, fill character and cell width to use
+    pub fn get_grid_formatted_with(&self, options: GridFormatOptions) -> String {
+        let mut formatted = String::new();
+
+        for y in 0..self.grid.rows() {
+            for cell in self.grid.iter_row_ids(y) {
+                formatted.push(options.open);
+                let content_len = match cell {
+                    Some(id) => {
+                        formatted.push_str(id);
+                        id.chars().count()
+                    }
+                    None => 0,
+                };
+                for _ in content_len..options.cell_width {
+                    formatted.push(options.empty);
+                }
+                formatted.push(options.close);
+            }
+            formatted.push('\n');
+        }
+
+        formatted
+    }
+
+    /// Renders the grid as text with a single character glyph per item, followed
+    /// by a legend mapping each glyph back to its id.
+    ///
+    /// Glyphs are assigned in the order ids are first seen, scanning rows top to
+    /// bottom, going through `0-9` then `a-z`. Past 36 items, every remaining id
+    /// is drawn as `?`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(2, 3);
+    /// grid.add_item("sidebar", 0, 0, 1, 2)?;
+    /// grid.add_item("header", 1, 0, 2, 1)?;
+    ///
+    /// assert_eq!(
+    ///     grid.get_grid_formatted_compact(),
+    ///     "[0][1][1]\n[0][ ][ ]\n\n0: sidebar\n1: header\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_grid_formatted_compact(&self) -> String {
+        const GLYPHS: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+        let mut legend: Vec<(char, &str)> = Vec::new();
+        let mut formatted = String::new();
+
+        for y in 0..self.grid.rows() {
+            for cell in self.grid.iter_row_ids(y) {
+                let glyph = match cell {
+                    Some(id) => match legend.iter().find(|(_, seen)| *seen == id) {
+                        Some((glyph, _)) => *glyph,
+                        None => {
+                            let glyph = GLYPHS.chars().nth(legend.len()).unwrap_or('?');
+                            legend.push((glyph, id));
+                            glyph
+                        }
+                    },
+                    None => ' ',
+                };
+                formatted.push('[');
+                formatted.push(glyph);
+                formatted.push(']');
+            }
+            formatted.push('\n');
+        }
+
+        formatted.push('\n');
+        for (glyph, id) in legend {
+            formatted.push_str(&format!("{glyph}: {id}\n"));
+        }
+
+        formatted
+    }
+
+    /// Returns whether the grid grows vertically when items are placed past its bottom edge.
+    pub fn can_expand_y(&self) -> bool {
+        self.grid.can_expand_y()
+    }
+
+    /// Sets whether the grid grows vertically when items are placed past its bottom edge.
+    ///
+    /// When disabled, operations that would need more rows fail with an
+    /// out of bounds error instead. Enabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    ///
+    /// let mut grid = GridEngine::new(4, 4);
+    /// grid.set_expand_y(false);
+    ///
+    /// assert!(grid.add_item("box1", 0, 3, 2, 2).is_err());
+    /// ```
+    pub fn set_expand_y(&mut self, can_expand_y: bool) {
+        self.grid.set_can_expand_y(can_expand_y);
+    }
+
+    /// Returns whether the grid grows horizontally when items are placed past its right edge.
+    pub fn can_expand_x(&self) -> bool {
+        self.grid.can_expand_x()
+    }
+
+    /// Sets whether the grid grows horizontally when items are placed past its right edge.
+    ///
+    /// Disabled by default, the number of columns is fixed.
+    pub fn set_expand_x(&mut self, can_expand_x: bool) {
+        self.grid.set_can_expand_x(can_expand_x);
+    }
+
+    /// Returns the maximum number of rows the grid can grow to.
+    pub fn max_rows(&self) -> Option<usize> {
+        self.grid.max_rows()
+    }
+
+    /// Sets the maximum number of rows the grid can grow to.
+    ///
+    /// Operations that would need more rows fail with an out of bounds error.
+    /// Rows already beyond the limit are kept. `None` removes the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_rows` - The row limit, or `None` for an unbounded grid
+    pub fn set_max_rows(&mut self, max_rows: Option<usize>) {
+        self.grid.set_max_rows(max_rows);
+    }
+
+    /// Returns how many rows the grid adds when it expands vertically.
+    pub fn auto_grow_policy(&self) -> AutoGrowPolicy {
+        self.grid.auto_grow_policy()
+    }
+
+    /// Sets how many rows the grid adds when it expands vertically.
+    ///
+    /// By default the grid grows by exactly the rows an access needs. Growing
+    /// ahead, in chunks or by doubling, saves reallocations when inserting
+    /// many items at once, at the cost of trailing empty rows. Growth never
+    /// goes past the row limit, see [`GridEngine::set_max_rows`].
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The growth policy to use
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::{AutoGrowPolicy, GridEngine};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 4);
+    /// grid.set_auto_grow_policy(AutoGrowPolicy::Chunked(8));
+    /// grid.add_item("box1", 0, 4, 1, 1)?;
+    ///
+    /// assert_eq!(grid.get_inner_grid().rows(), 12);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_auto_grow_policy(&mut self, policy: AutoGrowPolicy) {
+        self.grid.set_auto_grow_policy(policy);
+    }
+
+    /// Makes the engine read-only.
+    ///
+    /// While frozen, every operation changing the items or the grid, like
+    /// [`GridEngine::restore`] or [`GridEngine::reserve_rows`], fails with
+    /// [`GridEngineError::Frozen`] without mutating anything. Queries keep
+    /// working as usual.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// grid.freeze();
+    /// assert!(grid.move_item("box1", 4, 4).is_err());
+    /// assert_eq!(grid.get_nodes()[0].x(), &0);
+    ///
+    /// grid.thaw();
+    /// grid.move_item("box1", 4, 4)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Makes a frozen engine mutable again.
+    pub fn thaw(&mut self) {
+        self.frozen = false;
+    }
+
+    /// Returns whether the engine is frozen.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Returns the minimum number of rows kept when trimming the grid or
+    /// deleting rows.
+    ///
+    /// Defaults to the number of rows the grid was created with.
+    pub fn min_rows(&self) -> usize {
+        self.min_rows
+    }
+
+    /// Sets the minimum number of rows kept when trimming the grid or
+    /// deleting rows.
+    ///
+    /// This only affects [`GridEngine::trim_to_content`] and
+    /// [`GridEngine::delete_row`], the grid is never shrunk as a side effect of
+    /// calling this method.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The minimum number of rows
+    pub fn set_min_rows(&mut self, rows: usize) {
+        self.min_rows = rows;
+    }
+
+    /// Returns the rendering height hint of each row.
+    ///
+    /// Empty unless set, meaning every row has the same height.
+    pub fn row_heights(&self) -> &[u32] {
+        &self.row_heights
+    }
+
+    /// Sets the rendering height hint of each row, starting from the first one.
+    ///
+    /// These are layout hints carried along for renderers, e.g. a taller header
+    /// row, and are kept in snapshots. Placement still works in cells, so the
+    /// hints don't need to cover every row and are never checked against the
+    /// grid size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    ///
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.set_row_heights(vec![80, 40]);
+    ///
+    /// assert_eq!(grid.row_heights(), [80, 40]);
+    /// assert_eq!(grid.snapshot().row_heights(), [80, 40]);
+    /// ```
+    pub fn set_row_heights(&mut self, heights: Vec<u32>) {
+        self.row_heights = heights;
+    }
+
+    /// Returns the rendering width hint of each column.
+    ///
+    /// Empty unless set, meaning every column has the same width.
+    pub fn col_widths(&self) -> &[u32] {
+        &self.col_widths
+    }
+
+    /// Sets the rendering width hint of each column, starting from the first one.
+    ///
+    /// Like [`GridEngine::set_row_heights`], these are hints for renderers only.
+    pub fn set_col_widths(&mut self, widths: Vec<u32>) {
+        self.col_widths = widths;
+    }
+
+    /// Removes the empty rows at the bottom of the grid.
+    ///
+    /// The grid keeps enough rows to hold every item, and never goes below
+    /// [`GridEngine::min_rows`], regardless of its content.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the grid was trimmed
+    /// * `Err(GridEngineError::Frozen)` - If the engine is frozen
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 10);
+    /// grid.add_item("box1", 0, 8, 2, 2)?;
+    /// grid.remove_item("box1")?;
+    /// assert_eq!(grid.get_inner_grid().rows(), 10);
+    ///
+    /// grid.trim_to_content()?;
+    /// assert_eq!(grid.get_inner_grid().rows(), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "shrink_to_fit")]
+    pub fn trim_to_content(&mut self) -> Result<(), GridEngineError> {
+        if self.frozen {
+            return Err(GridEngineError::Frozen);
+        }
+
+        let content_rows = self
+            .items
+            .values()
+            .map(|node| node.y + node.h)
+            .max()
+            .unwrap_or(0);
+
+        self.grid.truncate_rows(content_rows.max(self.min_rows));
+        Ok(())
+    }
+
+    /// Expands the grid up front so it has at least `rows` rows.
+    ///
+    /// Useful before bulk loads that would otherwise grow the grid a few rows
+    /// at a time. The rows are added at once regardless of the vertical
+    /// expansion setting, up to [`GridEngine::max_rows`], and nothing happens
+    /// if the grid is already tall enough.
+    /// Reserved rows are empty, so [`GridEngine::trim_to_content`] removes them.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The minimum number of rows the grid should have
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the grid has at least `rows` rows, or as many as allowed
+    /// * `Err(GridEngineError::Frozen)` - If the engine is frozen
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 10);
+    /// grid.reserve_rows(200)?;
+    /// assert_eq!(grid.get_inner_grid().rows(), 200);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reserve_rows(&mut self, rows: usize) -> Result<(), GridEngineError> {
+        if self.frozen {
+            return Err(GridEngineError::Frozen);
+        }
+
+        let rows = self
+            .grid
+            .max_rows()
+            .map_or(rows, |max_rows| rows.min(max_rows));
+        let current = self.grid.rows();
+        if rows > current {
+            self.grid.expand_rows(rows - current);
+        }
+        Ok(())
+    }
+
+    /// Changes the number of columns, reflowing the items that no longer fit.
+    ///
+    /// Growing the grid only adds empty columns. When shrinking, items are
+    /// reflowed in row-major order, each one keeping its place when it can:
+    /// - Items wider than the grid are narrowed to the new width
+    /// - Items crossing the right edge are shifted left to end at the last column
+    /// - Items landing on an already reflowed one slide down, staying in their
+    ///   column, to the first rows where they fit
+    ///
+    /// No item is ever dropped. Listeners are notified with a single event
+    /// holding the moves and resizes, sent before the extra columns are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `cols` - The new number of columns, at least one is always kept
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the grid was resized
+    /// * `Err(GridEngineError)` - If the engine is frozen, an item finds no room
+    ///   within the row limit or applying the changes fails, in which case
+    ///   nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 12);
+    /// grid.add_item("left", 0, 0, 4, 2)?;
+    /// grid.add_item("right", 8, 0, 4, 2)?;
+    ///
+    /// grid.set_cols(6)?;
+    /// assert_eq!(grid.get_inner_grid().cols(), 6);
+    /// assert_eq!((grid.get_nodes()[1].x(), grid.get_nodes()[1].y()), (&2, &2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_cols(&mut self, cols: usize) -> Result<(), GridEngineError> {
+        if self.frozen {
+            return Err(GridEngineError::Frozen);
+        }
+
+        let cols = cols.max(1);
+        let mut nodes: Vec<Node> = self.items.values().cloned().collect();
+        nodes.sort_by_key(|node| (node.y, node.x, node.seq));
+
+        self.apply_on_working_copy(|working| {
+            for node in &nodes {
+                node.update_grid(&mut working.grid, UpdateGridOperation::Remove)?;
+            }
+            working.grid.resize_cols(cols);
+
+            for mut placed in nodes {
+                placed.w = placed.w.min(cols);
+                placed.x = placed.x.min(cols - placed.w);
+
+                while !colliding_ids(&working.grid, None, placed.position(), placed.size())
+                    .is_ok_and(|ids| ids.is_empty())
+                {
+                    if !working.grid.can_reach(placed.x, placed.y + placed.h - 1) {
+                        return Err(working
+                            .grid
+                            .out_of_bounds(placed.x, placed.y + placed.h - 1)
+                            .into());
+                    }
+                    placed.y += 1;
+                }
+
+                placed.update_grid(&mut working.grid, UpdateGridOperation::Add)?;
+                working.items.insert(placed.id.clone(), placed);
+            }
+            Ok(())
+        })?;
+        self.grid.resize_cols(cols);
+
+        Ok(())
+    }
+
+    /// Moves the items crossing the right edge back within the columns.
+    ///
+    /// A cleanup pass for responsive layouts, e.g. once the grid was narrowed
+    /// without reflowing, or restored from a layout made for more columns.
+    /// Items with `x + w > cols` are taken top to bottom, then left to right,
+    /// narrowed to the columns when wider, and each is placed at the first
+    /// free spot scanning rows top to bottom and columns left to right. Items
+    /// within the columns are never moved. Listeners are notified with a
+    /// single event holding the moves and resizes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Change>)` - The moves and resizes applied, empty if every item
+    ///   was within the columns
+    /// * `Err(GridEngineError)` - If an item finds no room within the row limit
+    ///   or applying the changes fails, in which case nothing is changed
+    pub fn relocate_offscreen_items(&mut self) -> Result<Vec<Change>, GridEngineError> {
+        let cols = self.grid.cols();
+        let mut offscreen: Vec<Node> = self
+            .items
+            .values()
+            .filter(|node| node.x + node.w > cols)
+            .cloned()
+            .collect();
+        if offscreen.is_empty() {
+            return Ok(Vec::new());
+        }
+        offscreen.sort_by_key(|node| (node.y, node.x, node.seq));
+
+        self.apply_on_working_copy(|working| {
+            for node in &offscreen {
+                node.update_grid(&mut working.grid, UpdateGridOperation::Remove)?;
+            }
+            for node in offscreen {
+                let size = Size::new(node.w.min(cols), node.h);
+                let spot = first_fit(&working.grid, size)?;
+                let placed = Node {
+                    w: size.w,
+                    ..node.with_position(spot.x, spot.y)
+                };
+
+                placed.update_grid(&mut working.grid, UpdateGridOperation::Add)?;
+                working.items.insert(placed.id.clone(), placed);
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the indices of the columns not occupied by any item.
+    ///
+    /// Computed from the items' footprints rather than by scanning every cell.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 4);
+    /// grid.add_item("box1", 1, 0, 2, 2)?;
+    ///
+    /// assert_eq!(grid.empty_columns(), vec![0, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn empty_columns(&self) -> Vec<usize> {
+        (0..self.grid.cols())
+            .filter(|col| {
+                !self
+                    .items
+                    .values()
+                    .any(|node| node.x <= *col && *col < node.x + node.w)
+            })
+            .collect()
+    }
+
+    /// Returns the indices of the rows not occupied by any item.
+    ///
+    /// Computed from the items' footprints rather than by scanning every cell.
+    pub fn empty_rows(&self) -> Vec<usize> {
+        (0..self.grid.rows())
+            .filter(|row| {
+                !self
+                    .items
+                    .values()
+                    .any(|node| node.y <= *row && *row < node.y + node.h)
+            })
+            .collect()
+    }
+
+    /// Summarizes the layout in a single structured value.
+    ///
+    /// Gathers the grid size, the item count, the occupancy ratio and the
+    /// placement of every item, sorted by id so the output is stable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 4);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// let summary = grid.describe();
+    /// assert_eq!(summary.item_count, 1);
+    /// assert_eq!(summary.occupancy_ratio, 0.25);
+    /// assert_eq!(summary.items[0].id, "box1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn describe(&self) -> LayoutSummary {
+        LayoutSummary {
+            rows: self.grid.rows(),
+            cols: self.grid.cols(),
+            item_count: self.items.len(),
+            occupancy_ratio: self.occupancy().fill_ratio,
+            items: self
+                .get_nodes()
+                .into_iter()
+                .map(|node| NodeSummary {
+                    id: node.id.clone(),
+                    x: node.x,
+                    y: node.y,
+                    w: node.w,
+                    h: node.h,
+                })
+                .collect(),
+        }
+    }
+
+    /// Computes occupancy statistics by scanning the grid once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// let stats = grid.occupancy();
+    /// assert_eq!(stats.occupied_cells, 4);
+    /// assert_eq!(stats.bounding_box, Some((0, 0, 2, 2)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn occupancy(&self) -> GridStats {
+        // Each row reports its occupied cells and leftmost and rightmost ones
+        let rows = map_indices(0..self.grid.rows(), |y| {
+            self.grid
+                .iter_row(y)
+                .enumerate()
+                .filter(|(_, cell)| cell.is_some())
+                .fold((0, None), |(count, span), (x, _)| {
+                    let span = match span {
+                        Some((min_x, max_x)) => Some((usize::min(min_x, x), usize::max(max_x, x))),
+                        None => Some((x, x)),
+                    };
+                    (count + 1, span)
+                })
+        });
+
+        let mut occupied_cells = 0;
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for (y, (count, span)) in rows.into_iter().enumerate() {
+            occupied_cells += count;
+
+            if let Some((row_min_x, row_max_x)) = span {
+                bounds = Some(match bounds {
+                    Some((min_x, min_y, max_x, max_y)) => (
+                        min_x.min(row_min_x),
+                        min_y.min(y),
+                        max_x.max(row_max_x),
+                        max_y.max(y),
+                    ),
+                    None => (row_min_x, y, row_max_x, y),
+                });
+            }
+        }
+
+        let total_cells = self.grid.rows() * self.grid.cols();
+        let fill_ratio = if total_cells == 0 {
+            0.0
+        } else {
+            occupied_cells as f64 / total_cells as f64
+        };
+
+        GridStats {
+            total_cells,
+            occupied_cells,
+            free_cells: total_cells - occupied_cells,
+            fill_ratio,
+            bounding_box: bounds.map(|(min_x, min_y, max_x, max_y)| {
+                (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)
+            }),
+        }
+    }
+
+    /// Finds the free cell closest to the given one, by Manhattan distance.
+    ///
+    /// The search goes through rings of increasing distance, up to `within`
+    /// cells away, and never looks past the current bounds. Cells at the same
+    /// distance are preferred top to bottom, then left to right.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the cell to start from
+    /// * `y` - Y coordinate of the cell to start from
+    /// * `within` - Maximum distance to search
+    ///
+    /// # Returns
+    ///
+    /// The `(x, y)` of the closest free cell, or `None` if there's none in range
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 4);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// assert_eq!(grid.nearest_free_cell(3, 3, 2), Some((3, 3)));
+    /// assert_eq!(grid.nearest_free_cell(0, 0, 2), Some((2, 0)));
+    /// assert_eq!(grid.nearest_free_cell(0, 0, 1), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nearest_free_cell(&self, x: usize, y: usize, within: usize) -> Option<(usize, usize)> {
+        let is_free =
+            |cell_x: usize, cell_y: usize| matches!(self.grid.peek(cell_x, cell_y), Some(None));
+
+        for distance in 0..=within {
+            for cell_y in y.saturating_sub(distance)..=y + distance {
+                let rest = distance - cell_y.abs_diff(y);
+                let candidates = [x.checked_sub(rest), Some(x + rest)];
+
+                for cell_x in candidates.into_iter().flatten() {
+                    if is_free(cell_x, cell_y) {
+                        return Some((cell_x, cell_y));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Counts how many items a hypothetical item would collide with.
+    ///
+    /// Nothing is moved or cloned, which makes it cheap enough to call for
+    /// every cell of the grid, e.g. to draw a collision heatmap. The part of
+    /// the footprint the grid can never reach has nothing to collide with.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the hypothetical item
+    /// * `y` - Y coordinate of the hypothetical item
+    /// * `w` - Width of the hypothetical item
+    /// * `h` - Height of the hypothetical item
+    ///
+    /// # Returns
+    ///
+    /// The number of distinct items overlapping the footprint
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.add_item("box2", 2, 0, 2, 2)?;
+    ///
+    /// assert_eq!(grid.count_collisions_at(1, 1, 2, 2), 2);
+    /// assert_eq!(grid.count_collisions_at(5, 5, 2, 2), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn count_collisions_at(&self, x: usize, y: usize, w: usize, h: usize) -> usize {
+        self.ids_within(None, Position::new(x, y), Size::new(w, h))
+            .len()
+    }
+
+    /// Returns the items intersecting a node that isn't part of the grid.
+    ///
+    /// Meant for hit-testing things like a drag ghost, built with
+    /// [`NodeBuilder`](crate::node::NodeBuilder) or [`Node::new`]. The ghost's
+    /// footprint is scanned cell by cell, an item sharing its id is left out,
+    /// and the part the grid can never reach has nothing to intersect.
+    ///
+    /// # Arguments
+    ///
+    /// * `ghost` - The detached node to test
+    ///
+    /// # Returns
+    ///
+    /// The intersecting items, in the row-major order their cells are found
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// use grid_engine::node::NodeBuilder;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// let ghost = NodeBuilder::new("ghost").position(1, 1).size(2, 2).build()?;
+    /// assert_eq!(grid.items_overlapping(&ghost)[0].id(), "box1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn items_overlapping(&self, ghost: &Node) -> Vec<&Node> {
+        self.ids_within(Some(&ghost.id), ghost.position(), ghost.size())
+            .into_iter()
+            .filter_map(|id| self.items.get(id))
+            .collect()
+    }
+
+    /// Collects the ids of the items occupying a footprint, clipped to the
+    /// part of it the grid can reach.
+    fn ids_within(&self, exclude: Option<&str>, position: Position, size: Size) -> Vec<&str> {
+        let Position { x, y } = position;
+        let w = (0..size.w)
+            .take_while(|dx| self.grid.can_reach(x + dx, y))
+            .count();
+        let h = (0..size.h)
+            .take_while(|dy| self.grid.can_reach(x, y + dy))
+            .count();
+
+        colliding_ids(&self.grid, exclude, position, Size::new(w, h)).unwrap_or_default()
+    }
+
+    /// Checks that the grid cells and the items agree with each other.
+    ///
+    /// Every occupied cell must reference an existing item whose footprint
+    /// covers it, and every cell of an item's footprint must point back to it.
+    /// Meant as a debugging aid, the engine keeps this invariant on its own.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the engine is consistent
+    /// * `Err(Vec<InconsistencyReport>)` - Every mismatch found, cells in
+    ///   row-major order followed by items sorted by id
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.move_item("box1", 4, 4)?;
+    ///
+    /// assert!(grid.validate().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<InconsistencyReport>> {
+        let cell_reports = map_indices(0..self.grid.rows(), |y| {
+            let mut reports = Vec::new();
+
+            for (x, cell) in self.grid.iter_row_ids(y).enumerate() {
+                let Some(id) = cell else {
+                    continue;
+                };
+
+                match self.items.get(id) {
+                    None => reports.push(InconsistencyReport::UnknownItem {
+                        id: id.to_string(),
+                        x,
+                        y,
+                    }),
+                    Some(node)
+                        if x < node.x
+                            || x >= node.x + node.w
+                            || y < node.y
+                            || y >= node.y + node.h =>
+                    {
+                        reports.push(InconsistencyReport::StrayCell {
+                            id: id.to_string(),
+                            x,
+                            y,
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            reports
+        });
+
+        let nodes: Vec<&Node> = self.items.values().collect();
+        let item_reports = map_indices(0..nodes.len(), |index| {
+            let node = nodes[index];
+            let mut reports = Vec::new();
+
+            for y in node.y..node.y + node.h {
+                for x in node.x..node.x + node.w {
+                    let found = self.grid.peek(x, y).flatten().map(str::to_string);
+                    let buried = self
+                        .stacked
+                        .get(&(x, y))
+                        .is_some_and(|ids| ids.contains(&node.id));
+                    if found.as_deref() != Some(node.id.as_str()) && !buried {
+                        reports.push(InconsistencyReport::MissingCell {
+                            id: node.id.to_string(),
+                            x,
+                            y,
+                            found,
+                        });
+                    }
+                }
+            }
+
+            reports
+        });
+
+        let reports: Vec<InconsistencyReport> = cell_reports
+            .into_iter()
+            .chain(item_reports)
+            .flatten()
+            .collect();
+
+        if reports.is_empty() {
+            Ok(())
+        } else {
+            Err(reports)
+        }
+    }
+
+    /// Clears every cell referencing an id that isn't a known item.
+    ///
+    /// A recovery tool for corrupted states, e.g. restored from a damaged
+    /// snapshot, fixing the [`InconsistencyReport::UnknownItem`] mismatches
+    /// reported by [`GridEngine::validate`]. Items are left untouched, so no
+    /// listener is notified.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of cells cleared
+    /// * `Err(GridEngineError::Frozen)` - If the engine is frozen
+    pub fn repair(&mut self) -> Result<usize, GridEngineError> {
+        if self.frozen {
+            return Err(GridEngineError::Frozen);
+        }
+
+        let orphans: Vec<(usize, usize)> = self
+            .grid
+            .iter_cells()
+            .filter(|(_, _, cell)| cell.is_some_and(|id| !self.items.contains_key(id)))
+            .map(|(x, y, _)| (x, y))
+            .collect();
+
+        for &(x, y) in &orphans {
+            // Orphans come from the current cells, so they're always in bounds
+            let _ = self.grid.set(x, y, None);
+        }
+
+        Ok(orphans.len())
+    }
+
+    /// Returns the tightest rectangle enclosing every item.
+    ///
+    /// Computed from the items' footprints rather than by scanning every cell.
+    ///
+    /// # Returns
+    ///
+    /// * `Some((x, y, w, h))` - The enclosing rectangle
+    /// * `None` - If the grid holds no items
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// assert_eq!(grid.bounding_box(), None);
+    ///
+    /// grid.add_item("box1", 2, 1, 2, 2)?;
+    /// assert_eq!(grid.bounding_box(), Some((2, 1, 2, 2)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        self.items
+            .values()
+            .map(|node| (node.x, node.y, node.x + node.w, node.y + node.h))
+            .reduce(|(min_x, min_y, max_x, max_y), (x, y, end_x, end_y)| {
+                (
+                    min_x.min(x),
+                    min_y.min(y),
+                    max_x.max(end_x),
+                    max_y.max(end_y),
+                )
+            })
+            .map(|(min_x, min_y, max_x, max_y)| (min_x, min_y, max_x - min_x, max_y - min_y))
+    }
+
+    /// Returns the item shown at a cell.
+    ///
+    /// When items overlap, see [`CollisionStrategy::Stack`], this is the
+    /// topmost one, the items buried under it are still listed by
+    /// [`GridEngine::get_nodes`]. The grid is never expanded.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the cell
+    /// * `y` - Y coordinate of the cell
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::{CollisionStrategy, GridEngine};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.set_collision_strategy(CollisionStrategy::Stack);
+    /// grid.add_item("card1", 0, 0, 2, 2)?;
+    /// grid.add_item("card2", 1, 1, 2, 2)?;
+    ///
+    /// assert_eq!(grid.item_at(1, 1).map(|node| node.id()), Some("card2"));
+    /// assert_eq!(grid.item_at(0, 0).map(|node| node.id()), Some("card1"));
+    /// assert!(grid.item_at(5, 5).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn item_at(&self, x: usize, y: usize) -> Option<&Node> {
+        let id = self.grid.peek(x, y)??;
+        self.items.get(id)
+    }
+
+    /// Returns the distinct items whose footprint crosses the given row.
+    ///
+    /// Items are returned left to right, and the grid is never expanded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("a", 0, 0, 2, 2)?;
+    /// grid.add_item("b", 4, 1, 2, 2)?;
+    ///
+    /// let ids: Vec<&str> = grid.nodes_in_row(1).iter().map(|n| n.id()).collect();
+    /// assert_eq!(ids, vec!["a", "b"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nodes_in_row(&self, y: usize) -> Vec<&Node> {
+        self.distinct_nodes((0..self.grid.cols()).map(|x| (x, y)))
+    }
+
+    /// Returns the distinct items whose footprint crosses the given column.
+    ///
+    /// Items are returned top to bottom, and the grid is never expanded.
+    pub fn nodes_in_col(&self, x: usize) -> Vec<&Node> {
+        self.distinct_nodes((0..self.grid.rows()).map(|y| (x, y)))
+    }
+
+    /// Collects the distinct items found in the given cells, in visiting order.
+    fn distinct_nodes(&self, cells: impl Iterator<Item = (usize, usize)>) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = Vec::new();
+
+        for (x, y) in cells {
+            if let Some(Some(id)) = self.grid.peek(x, y) {
+                if nodes.iter().any(|node| node.id == id) {
+                    continue;
+                }
+                if let Some(node) = self.items.get(id) {
+                    nodes.push(node);
+                }
+            }
+        }
+
+        nodes
+    }
+
+    /// Checks whether a rectangular region is entirely empty.
+    ///
+    /// The scan stops at the first occupied cell. Cells outside the current grid
+    /// bounds count as not free, and the grid is never expanded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// assert!(grid.is_region_free(2, 0, 2, 2));
+    /// assert!(!grid.is_region_free(1, 1, 2, 2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_region_free(&self, x: usize, y: usize, w: usize, h: usize) -> bool {
+        (x..x + w).all(|cell_x| {
+            (y..y + h).all(|cell_y| matches!(self.grid.peek(cell_x, cell_y), Some(None)))
+        })
+    }
+
+    /// Counts how many more `w`x`h` items could be placed in the current free space.
+    ///
+    /// The count comes from a greedy simulation: cells are scanned in row-major
+    /// order and an item is placed at the first position where it fits, on a copy
+    /// of the occupancy map. It is not an optimal packing, a smarter arrangement
+    /// may fit more items. The grid is never expanded, only the current rows count.
+    ///
+    /// # Arguments
+    ///
+    /// * `w` - Width of the items
+    /// * `h` - Height of the items
+    ///
+    /// # Returns
+    ///
+    /// The number of items that fit, 0 if either dimension is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 4);
+    /// assert_eq!(grid.capacity_for(2, 2), 4);
+    ///
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// assert_eq!(grid.capacity_for(2, 2), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn capacity_for(&self, w: usize, h: usize) -> usize {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        if w == 0 || h == 0 || w > cols || h > rows {
+            return 0;
+        }
+
+        let mut occupied: Vec<bool> = self.grid.iter().map(|cell| cell.is_some()).collect();
+        let mut capacity = 0;
+
+        for y in 0..=rows - h {
+            for x in 0..=cols - w {
+                let fits = (y..y + h)
+                    .all(|cell_y| (x..x + w).all(|cell_x| !occupied[cell_y * cols + cell_x]));
+                if !fits {
+                    continue;
+                }
+
+                for cell_y in y..y + h {
+                    for cell_x in x..x + w {
+                        occupied[cell_y * cols + cell_x] = true;
+                    }
+                }
+                capacity += 1;
+            }
+        }
+
+        capacity
+    }
+
+    /// Finds the item covering the most cells within a rectangular region.
+    ///
+    /// Cells outside the current grid bounds are ignored, and the grid is never
+    /// expanded. When two items cover the same number of cells, the one with the
+    /// smallest id wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the region's top-left corner
+    /// * `y` - Y coordinate of the region's top-left corner
+    /// * `w` - Width of the region
+    /// * `h` - Height of the region
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&Node)` - The item occupying the majority of the region
+    /// * `None` - If the region holds no items
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("a", 0, 0, 2, 2)?;
+    /// grid.add_item("b", 2, 0, 2, 2)?;
+    ///
+    /// let dominant = grid.dominant_item_in(1, 0, 3, 2).unwrap();
+    /// assert_eq!(dominant.id(), "b");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dominant_item_in(&self, x: usize, y: usize, w: usize, h: usize) -> Option<&Node> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+
+        for cell_x in x..x + w {
+            for cell_y in y..y + h {
+                if let Some(Some(id)) = self.grid.peek(cell_x, cell_y) {
+                    *counts.entry(id).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut dominant: Option<(&str, usize)> = None;
+        for (id, count) in counts {
+            match dominant {
+                Some((_, best)) if best >= count => {}
+                _ => dominant = Some((id, count)),
+            }
+        }
+
+        dominant.and_then(|(id, _)| self.items.get(id))
+    }
+
+    /// Copies the items fully contained in a region, relative to its corner.
+    ///
+    /// The returned nodes are detached from the grid, with coordinates
+    /// translated so the region's top-left corner is (0, 0), ready to be fed
+    /// to [`GridEngine::paste_region`]. Items only partly inside the region
+    /// are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the region
+    /// * `y` - Y coordinate of the region
+    /// * `w` - Width of the region
+    /// * `h` - Height of the region
+    ///
+    /// # Returns
+    ///
+    /// The copied nodes, in creation order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 2, 3, 2, 2)?;
+    /// grid.add_item("box2", 3, 5, 4, 1)?;
+    ///
+    /// let copied = grid.clone_region(2, 3, 3, 3);
+    /// assert_eq!(copied.len(), 1);
+    /// assert_eq!((copied[0].x(), copied[0].y()), (&0, &0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clone_region(&self, x: usize, y: usize, w: usize, h: usize) -> Vec<Node> {
+        self.get_nodes_by_creation()
+            .into_iter()
+            .filter(|node| {
+                node.x >= x && node.y >= y && node.right() <= x + w && node.bottom() <= y + h
+            })
+            .map(|node| {
+                let mut copy = node.clone();
+                copy.x -= x;
+                copy.y -= y;
+                copy
+            })
+            .collect()
+    }
+
+    /// Adds an item to the grid at the specified position.
+    ///
+    /// If the new item would collide with existing items, those items are
+    /// automatically repositioned to avoid overlap. With
+    /// [`AddPlacement::FindNextFree`] the new item is placed further down instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for the item
+    /// * `x` - X coordinate (column) for item placement
+    /// * `y` - Y coordinate (row) for item placement
+    /// * `w` - Width of the item in grid cells
+    /// * `h` - Height of the item in grid cells
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&Node)` - Reference to the newly added node
+    /// * `Err(GridEngineError)` - If item already exists or placement fails
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1".to_string(), 0, 0, 2, 2)?; // 2x2 item at top-left
+    ///
+    /// // Check if the item was added correctly
+    /// let item = grid.get_nodes();
+    /// assert_eq!(item.len(), 1);
+    /// assert_eq!(item[0].id(), "box1");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_item(
+        &mut self,
+        id: impl Into<String>,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+    ) -> Result<&Node, GridEngineError> {
+        let id = id.into();
+        if self.items.contains_key(&id) {
+            return Err(GridEngineError::Item(ItemError::ItemAlreadyExists { id }));
+        };
+        self.check_dimensions(w, h)?;
+
+        let node = self.new_node(id, x, y, w, h);
+        let node_id = node.id.to_string();
+        let node = match self.shrunk_height(&node, x, y, &self.grid)? {
+            Some(h) => Node { h, ..node },
+            None => node,
+        };
+
+        let y = match self.add_placement {
+            _ if self.collision_strategy == CollisionStrategy::Stack => y,
+            AddPlacement::PushExisting => {
+                // A cascade can fail halfway, e.g. on a footer item with no room
+                let resolved =
+                    self.handle_collision(&node, x, y, &mut self.grid.clone(), &mut Vec::new());
+                if resolved.is_err() {
+                    self.pending_changes.clear();
+                }
+                resolved?
+            }
+            AddPlacement::FindNextFree => self.next_free_y(&node, x, y),
+        };
+
+        self.create_add_change(node.with_position(x, y));
+
+        self.apply_changes(&self.pending_changes.clone())?;
+        self.pending_changes.clear();
+
+        let node = self
+            .items
+            .get(&node_id)
+            .ok_or(InnerGridError::MismatchedGridItem { id: node_id })?;
+        Ok(node)
+    }
+
+    /// Adds a new item to the grid, with typed coordinates.
+    ///
+    /// Same as [`GridEngine::add_item`], but the position and the size can't be
+    /// swapped by mistake. Tuples are accepted too, as `(x, y)` and `(w, h)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for the item
+    /// * `position` - Position of the item's top-left corner
+    /// * `size` - Dimensions of the item in grid cells
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::geometry::{Position, Size};
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// let node = grid.add_item_at("box1", Position::new(4, 1), Size::new(2, 3))?;
+    ///
+    /// assert_eq!(node.position(), Position::new(4, 1));
+    /// assert_eq!(node.size(), Size::new(2, 3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// A size can't be passed as the position:
+    ///
+    /// ```compile_fail
+    /// use grid_engine::geometry::{Position, Size};
+    /// use grid_engine::grid_engine::GridEngine;
+    ///
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item_at("box1", Size::new(2, 3), Position::new(4, 1));
+    /// ```
+    pub fn add_item_at(
+        &mut self,
+        id: impl Into<String>,
+        position: impl Into<Position>,
+        size: impl Into<Size>,
+    ) -> Result<&Node, GridEngineError> {
+        let (position, size) = (position.into(), size.into());
+        self.add_item(id, position.x, position.y, size.w, size.h)
+    }
+
+    /// Adds several items at once, all or nothing.
+    ///
+    /// Items are placed in order, exactly as consecutive [`GridEngine::add_item`]
+    /// calls would, but listeners are notified with a single event holding the
+    /// net changes. If any item fails, nothing is changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The `(id, x, y, w, h)` of each item to add
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Change>)` - The changes applied, moves of displaced items
+    ///   followed by the additions
+    /// * `Err(GridEngineError)` - If an id is repeated or already exists, or an
+    ///   item can't be placed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_items(vec![
+    ///     ("header".to_string(), 0, 0, 10, 1),
+    ///     ("body".to_string(), 0, 1, 10, 4),
+    /// ])?;
+    ///
+    /// assert_eq!(grid.total_items(), 2);
+    /// assert!(grid.add_items(vec![("body".to_string(), 0, 5, 1, 1)]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_items(
+        &mut self,
+        items: Vec<(String, usize, usize, usize, usize)>,
+    ) -> Result<Vec<Change>, GridEngineError> {
+        let mut ids = BTreeSet::new();
+        for (id, ..) in &items {
+            if self.items.contains_key(id) || !ids.insert(id) {
+                return Err(GridEngineError::Item(ItemError::ItemAlreadyExists {
+                    id: id.to_string(),
+                }));
+            }
+        }
+
+        self.apply_on_working_copy(|working| {
+            for (id, x, y, w, h) in items {
+                working.add_item(id, x, y, w, h)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Pastes copied nodes with their top-left corner at the given point.
+    ///
+    /// Each node is added offset by `(at_x, at_y)`, resolving collisions like
+    /// [`GridEngine::add_items`] does, all or nothing and with a single event.
+    /// Ids already taken get a `-copy` suffix, numbered from `-copy-2` on when
+    /// that is taken too. Pasted items get a fresh creation order and the
+    /// default weight.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - Nodes relative to (0, 0), as returned by [`GridEngine::clone_region`]
+    /// * `at_x` - X coordinate to paste at
+    /// * `at_y` - Y coordinate to paste at
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<String>)` - The ids given to the pasted items, in order
+    /// * `Err(GridEngineError)` - If an item can't be placed, in which case
+    ///   nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// let copied = grid.clone_region(0, 0, 2, 2);
+    /// let ids = grid.paste_region(copied, 5, 5)?;
+    /// assert_eq!(ids, ["box1-copy"]);
+    /// assert_eq!(grid.get_nodes()[1].x(), &5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn paste_region(
+        &mut self,
+        items: Vec<Node>,
+        at_x: usize,
+        at_y: usize,
+    ) -> Result<Vec<String>, GridEngineError> {
+        let mut taken: BTreeSet<String> = self.items.keys().cloned().collect();
+        let mut placements = Vec::new();
+
+        for node in items {
+            let mut id = node.id.clone();
+            let mut copies = 1;
+            while taken.contains(&id) {
+                id = match copies {
+                    1 => format!("{}-copy", node.id),
+                    n => format!("{}-copy-{n}", node.id),
+                };
+                copies += 1;
+            }
+            taken.insert(id.clone());
+
+            placements.push((id, node.x + at_x, node.y + at_y, node.w, node.h));
+        }
+
+        let ids = placements.iter().map(|(id, ..)| id.clone()).collect();
+        self.add_items(placements)?;
+
+        Ok(ids)
+    }
+
+    /// Places a bag of sized items at once, packing them first-fit decreasing.
+    ///
+    /// Items are taken biggest area first, keeping the given order among equal
+    /// areas, and each is placed at the first free spot scanning rows top to
+    /// bottom and columns left to right, so existing items are never moved.
+    /// The grid grows as needed within its row limit. Listeners are notified
+    /// with a single event holding the additions.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The `(id, w, h)` of each item to place
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Change>)` - The additions, sorted by id
+    /// * `Err(GridEngineError)` - If an id is repeated or already exists, a size
+    ///   is invalid or an item finds no room, in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 4);
+    /// grid.pack(vec![
+    ///     ("small".to_string(), 1, 1),
+    ///     ("big".to_string(), 3, 2),
+    /// ])?;
+    ///
+    /// assert_eq!(grid.get_nodes()[0].id(), "big");
+    /// assert_eq!((grid.get_nodes()[0].x(), grid.get_nodes()[0].y()), (&0, &0));
+    /// assert_eq!((grid.get_nodes()[1].x(), grid.get_nodes()[1].y()), (&3, &0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "first_fit_decreasing")]
+    pub fn pack(
+        &mut self,
+        mut items: Vec<(String, usize, usize)>,
+    ) -> Result<Vec<Change>, GridEngineError> {
+        let mut ids = BTreeSet::new();
+        for (id, w, h) in &items {
+            if self.items.contains_key(id) || !ids.insert(id) {
+                return Err(GridEngineError::Item(ItemError::ItemAlreadyExists {
+                    id: id.to_string(),
+                }));
+            }
+            self.check_dimensions(*w, *h)?;
+        }
+
+        items.sort_by_key(|(_, w, h)| std::cmp::Reverse(w * h));
+
+        self.apply_on_working_copy(|working| {
+            for (id, w, h) in items {
+                let spot = first_fit(&working.grid, Size::new(w, h))?;
+                working.add_item(id, spot.x, spot.y, w, h)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Adds an item to the grid only if the target position is free.
+    ///
+    /// Unlike [`GridEngine::add_item`], existing items are never repositioned.
+    /// This is useful to verify a layout that is expected to be collision-free.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for the item
+    /// * `x` - X coordinate (column) for item placement
+    /// * `y` - Y coordinate (row) for item placement
+    /// * `w` - Width of the item in grid cells
+    /// * `h` - Height of the item in grid cells
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&Node)` - Reference to the newly added node
+    /// * `Err(GridEngineError)` - If item already exists, the position is occupied
+    ///   (listing the blocking items) or placement fails
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.try_add_item_strict("box1", 0, 0, 2, 2)?;
+    ///
+    /// assert!(grid.try_add_item_strict("box2", 1, 1, 2, 2).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_add_item_strict(
+        &mut self,
+        id: impl Into<String>,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+    ) -> Result<&Node, GridEngineError> {
+        let id = id.into();
+        if self.items.contains_key(&id) {
+            return Err(GridEngineError::Item(ItemError::ItemAlreadyExists { id }));
+        };
+        self.check_dimensions(w, h)?;
+
+        let node = self.new_node(id, x, y, w, h);
+
+        let blockers = self
+            .will_collides_with(&node, x, y, &self.grid)?
+            .iter()
+            .map(|n| n.id.to_string())
+            .collect::<Vec<String>>();
+
+        if !blockers.is_empty() {
+            return Err(GridEngineError::Item(ItemError::PositionOccupied {
+                ids: blockers,
+            }));
+        }
+
+        let node_id = node.id.to_string();
+        self.create_add_change(node);
+
+        self.apply_changes(&self.pending_changes.clone())?;
+        self.pending_changes.clear();
+
+        let node = self
+            .items
+            .get(&node_id)
+            .ok_or(InnerGridError::MismatchedGridItem { id: node_id })?;
+        Ok(node)
+    }
+
+    fn create_remove_change(&mut self, node: &Node) {
+        self.pending_changes.push(Change::Remove(RemoveChangeData {
+            value: node.clone(),
+        }));
+    }
+
+    /// Removes an item from the grid by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to remove
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Node)` - The removed node
+    /// * `Err(GridEngineError)` - If item doesn't exist
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    ///
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1".to_string(), 0, 0, 2, 2)?;
+    /// grid.remove_item("box1")?; // Removes the item
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove_item(&mut self, id: &str) -> Result<Node, GridEngineError> {
+        let node = match self.items.get(id) {
+            Some(node) => node,
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: id.to_string(),
+            }))?,
+        }
+        .clone();
+
+        self.create_remove_change(&node);
+
+        self.apply_changes(&self.pending_changes.clone())?;
+        self.pending_changes.clear();
+        Ok(node)
+    }
+
+    /// Removes an item if it exists, or adds it otherwise.
+    ///
+    /// Handy for palettes where clicking a widget toggles it on the grid. When
+    /// the item is added it goes through [`GridEngine::add_item`], collisions
+    /// included, and when it is removed the position and size are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to toggle
+    /// * `x` - X coordinate (column) used if the item is added
+    /// * `y` - Y coordinate (row) used if the item is added
+    /// * `w` - Width used if the item is added
+    /// * `h` - Height used if the item is added
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Toggled)` - Whether the item was added or removed
+    /// * `Err(GridEngineError)` - If adding or removing the item fails
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::{GridEngine, Toggled};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    ///
+    /// assert!(matches!(grid.toggle_item("box1", 0, 0, 2, 2)?, Toggled::Added(_)));
+    /// assert!(matches!(grid.toggle_item("box1", 0, 0, 2, 2)?, Toggled::Removed(_)));
+    /// assert!(grid.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn toggle_item(
+        &mut self,
+        id: impl Into<String>,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+    ) -> Result<Toggled<'_>, GridEngineError> {
+        let id = id.into();
+        if self.items.contains_key(&id) {
+            return Ok(Toggled::Removed(self.remove_item(&id)?));
+        }
+
+        Ok(Toggled::Added(self.add_item(id, x, y, w, h)?))
+    }
+
+    /// Removes several items from the grid at once.
+    ///
+    /// Every id is checked before anything is touched, so either all items are
+    /// removed or none is. Listeners are notified with a single event holding
+    /// one removal per item. Ids repeated in `ids` are removed once.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of the items to remove
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Node>)` - The removed nodes, in the order of `ids`
+    /// * `Err(GridEngineError)` - If any item doesn't exist
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.add_item("box2", 2, 0, 2, 2)?;
+    ///
+    /// let removed = grid.remove_items(&["box1", "box2"])?;
+    /// assert_eq!(removed.len(), 2);
+    /// assert!(grid.get_nodes().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove_items(&mut self, ids: &[&str]) -> Result<Vec<Node>, GridEngineError> {
+        let mut nodes: Vec<Node> = Vec::with_capacity(ids.len());
+        for id in ids {
+            let node = match self.items.get(*id) {
+                Some(node) => node,
+                None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                    id: id.to_string(),
+                }))?,
+            };
+
+            if !nodes.iter().any(|removed| removed.id == node.id) {
+                nodes.push(node.clone());
+            }
+        }
+
+        for node in &nodes {
+            self.create_remove_change(node);
+        }
+
+        self.apply_changes(&self.pending_changes.clone())?;
+        self.pending_changes.clear();
+        Ok(nodes)
+    }
+
+    /// Changes the id of an existing item.
+    ///
+    /// The item keeps its position and size, only the id stored in the items map
+    /// and in every cell it occupies is rewritten. Listeners are notified with a
+    /// single event holding the removal of the old id and the addition of the new one.
+    ///
+    /// The item keeps its weight and creation order, use [`GridEngine::replace_item`]
+    /// to put a different item in its place instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - Current ID of the item
+    /// * `new` - New ID for the item
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the item was renamed
+    /// * `Err(GridEngineError)` - If `old` doesn't exist or `new` already exists,
+    ///   in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("temp_1", 0, 0, 2, 2)?;
+    /// grid.rename_item("temp_1", "widget_42")?;
+    ///
+    /// assert_eq!(grid.get_nodes()[0].id(), "widget_42");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rename_item(&mut self, old: &str, new: &str) -> Result<(), GridEngineError> {
+        let node = match self.items.get(old) {
+            Some(node) => node.clone(),
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: old.to_string(),
+            }))?,
+        };
+
+        if self.items.contains_key(new) {
+            return Err(GridEngineError::Item(ItemError::ItemAlreadyExists {
+                id: new.to_string(),
+            }));
+        }
+
+        let renamed = Node {
+            id: new.to_string(),
+            ..node.clone()
+        };
+
+        self.create_remove_change(&node);
+        self.create_add_change(renamed);
+
+        self.apply_changes(&self.pending_changes.clone())?;
+        self.pending_changes.clear();
+
+        Ok(())
+    }
+
+    /// Changes the id of every item at once.
+    ///
+    /// Each item keeps its position, size, weight and creation order, as with
+    /// [`GridEngine::rename_item`]. Listeners are notified with a single event
+    /// holding the removal of every old id followed by the addition of every new
+    /// one, items whose id doesn't change are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Function mapping each current id to its new id
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the items were renamed
+    /// * `Err(GridEngineError)` - If two items would end up with the same id,
+    ///   in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("a", 0, 0, 2, 2)?;
+    /// grid.add_item("b", 2, 0, 2, 2)?;
+    ///
+    /// grid.map_ids(|id| format!("w_{id}"))?;
+    /// assert_eq!(grid.get_nodes()[0].id(), "w_a");
+    /// assert_eq!(grid.get_nodes()[1].id(), "w_b");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_ids<F>(&mut self, f: F) -> Result<(), GridEngineError>
+    where
+        F: Fn(&str) -> String,
+    {
+        let mut ids = BTreeSet::new();
+        let mut renamed: Vec<(Node, Node)> = Vec::new();
+        for node in self.items.values() {
+            let id = f(&node.id);
+            if !ids.insert(id.clone()) {
+                return Err(GridEngineError::Item(ItemError::ItemAlreadyExists { id }));
+            }
+
+            if id != node.id {
+                renamed.push((node.clone(), Node { id, ..node.clone() }));
+            }
+        }
+
+        for (old, _) in &renamed {
+            self.create_remove_change(old);
+        }
+        for (_, new) in renamed {
+            self.create_add_change(new);
+        }
+
+        if !self.pending_changes.is_empty() {
+            self.apply_changes(&self.pending_changes.clone())?;
+            self.pending_changes.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Replaces an existing item with a new one occupying the exact same cells.
+    ///
+    /// Unlike [`GridEngine::rename_item`], the new item doesn't inherit anything
+    /// but the footprint: it starts with the default weight and is created after
+    /// every existing item. Useful to swap a placeholder for the real content.
+    /// Listeners are notified with a single event holding the removal of the old
+    /// item and the addition of the new one.
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - ID of the item to replace
+    /// * `new_id` - ID of the item taking its place
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the item was replaced
+    /// * `Err(GridEngineError)` - If `old` doesn't exist or `new_id` already exists,
+    ///   in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("placeholder", 2, 2, 3, 2)?;
+    /// grid.replace_item("placeholder", "chart".to_string())?;
+    ///
+    /// let chart = grid.get_nodes()[0];
+    /// assert_eq!(chart.id(), "chart");
+    /// assert_eq!((chart.x(), chart.w()), (&2, &3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn replace_item(&mut self, old: &str, new_id: String) -> Result<(), GridEngineError> {
+        let node = match self.items.get(old) {
+            Some(node) => node.clone(),
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: old.to_string(),
+            }))?,
+        };
+
+        if self.items.contains_key(&new_id) {
+            return Err(GridEngineError::Item(ItemError::ItemAlreadyExists {
+                id: new_id,
+            }));
+        }
+
+        let replacement = self.new_node(new_id, node.x, node.y, node.w, node.h);
+
+        self.create_remove_change(&node);
+        self.create_add_change(replacement);
+
+        self.apply_changes(&self.pending_changes.clone())?;
+        self.pending_changes.clear();
+
+        Ok(())
+    }
+
+    /// Checks if a node would collide with any existing items at the specified position.
+    ///
+    /// This is used internally to detect potential collisions before making grid changes.
+    /// It considers the node's dimensions and any existing items in the target area.
+    ///
+    /// The grid is never expanded: cells past its bounds that it could grow into
+    /// count as empty, while cells it can't reach are an error.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<&Node>)` - List of nodes that would collide with the given node
+    /// * `Err(InnerGridError)` - If position check fails (e.g., out of bounds)
+    fn will_collides_with(
+        &self,
+        node: &Node,
+        x: usize,
+        y: usize,
+        grid: &InnerGrid,
+    ) -> Result<Vec<&Node>, InnerGridError> {
+        colliding_ids(
+            grid,
+            Some(&node.id),
+            Position::new(x, y),
+            Size::new(node.w, node.h),
+        )?
+        .into_iter()
+        .map(|id| {
+            self.items
+                .get(id)
+                .ok_or(InnerGridError::MismatchedGridItem { id: id.to_string() })
+        })
+        .collect()
+    }
+
+    /// Handles collision resolution when adding or moving items.
+    ///
+    /// When a collision is detected, this method:
+    /// 1. Identifies all affected items
+    /// 2. Calculates new positions for colliding items
+    /// 3. Creates appropriate move changes to relocate affected items
+    ///
+    /// The default collision resolution strategy moves affected items downward,
+    /// which may trigger dynamic grid expansion in the y-axis. With
+    /// [`CollisionStrategy::FlowRight`] they are moved right instead, wrapping
+    /// to the next row when they don't fit. With [`CollisionStrategy::Auto`]
+    /// each item is moved along the axis with the smallest overlap.
+    ///
+    /// Items heavier than the incoming node are never displaced, instead the
+    /// incoming node is settled below them before resolving the remaining collisions.
+    /// With [`CollisionStrategy::StackInColumn`] the same goes for items in
+    /// another lane, so only items spanning the exact same columns are pushed down.
+    ///
+    /// Colliding items are resolved sorted by y, then x, then id.
+    ///
+    /// Items in the sticky footer, see [`GridEngine::set_footer_row`], are
+    /// pushed up above the incoming node rather than down.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The incoming node
+    /// * `x` - Target x coordinate of the incoming node
+    /// * `y` - Target y coordinate of the incoming node
+    /// * `grid` - The working grid to check for collisions
+    /// * `landing` - Where the nodes whose collisions are being resolved will
+    ///   land, outermost first. They aren't on the working grid yet, so this
+    ///   keeps items pushed up from landing on them. Empty for a new cascade
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The y coordinate where the incoming node ends up
+    /// * `Err(InnerGridError)` - If the collision check fails, or a footer item
+    ///   has no room above
+    fn handle_collision(
+        &mut self,
+        node: &Node,
+        x: usize,
+        y: usize,
+        grid: &mut InnerGrid,
+        landing: &mut Vec<Node>,
+    ) -> Result<usize, InnerGridError> {
+        if self.collision_strategy == CollisionStrategy::Stack {
+            return Ok(y);
+        }
+
+        let y = self.settle_below_immovable(node, x, y, grid)?;
+
+        landing.push(node.with_position(x, y));
+        let resolved = self.resolve_collisions(node, x, y, grid, landing);
+        landing.pop();
+        resolved?;
+
+        Ok(y)
+    }
+
+    /// Moves the items colliding with a node out of its way, see
+    /// [`GridEngine::handle_collision`].
+    fn resolve_collisions(
+        &mut self,
+        node: &Node,
+        x: usize,
+        y: usize,
+        grid: &mut InnerGrid,
+        landing: &mut Vec<Node>,
+    ) -> Result<(), InnerGridError> {
+        // Resolved top to bottom, then left to right, so the outcome only
+        // depends on the layout and not on the order cells are scanned in
+        let mut collides_with = self.will_collides_with(node, x, y, grid)?;
+        collides_with.sort_by_key(|n| (n.y, n.x, n.id.as_str()));
+        let collides_with = collides_with
+            .iter()
+            .map(|n| n.id.clone())
+            .collect::<Vec<String>>();
+
+        for id in collides_with {
+            // An earlier cascade may have already pushed it out of the way
+            let Some(collided) = self.pending_node(&id) else {
+                continue;
+            };
+            if !collided.intersects(&node.with_position(x, y)) {
+                continue;
+            }
+
+            let gap = self.item_gap;
+            let (new_x, new_y) = match self.collision_strategy {
+                CollisionStrategy::FlowRight if x + node.w + gap + collided.w <= grid.cols() => {
+                    (x + node.w + gap, collided.y)
+                }
+                CollisionStrategy::FlowRight => (0, y + node.h + gap),
+                CollisionStrategy::Auto
+                    if x + node.w + gap + collided.w <= grid.cols()
+                        && x + node.w - collided.x < y + node.h - collided.y =>
+                {
+                    (x + node.w + gap, collided.y)
+                }
+                _ => (collided.x, y + node.h + gap),
+            };
+            let (new_x, new_y) = match self.footer_row {
+                Some(row) if collided.y >= row && new_y > collided.y => (
+                    collided.x,
+                    self.footer_escape_y(&collided, y, grid, landing)?,
+                ),
+                _ => (new_x, new_y),
+            };
+            self.create_move_change(collided, new_x, new_y, grid, landing)?;
+        }
+
+        Ok(())
+    }
+
+    /// Finds where a footer item goes when the incoming node lands on it: right
+    /// above the node's top row `y`, keeping the item gap.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The y coordinate of the footer item above the node
+    /// * `Err(InnerGridError::FooterPushBlocked)` - If the grid's top, another
+    ///   item or a node still being placed leaves no room there
+    fn footer_escape_y(
+        &self,
+        collided: &Node,
+        y: usize,
+        grid: &InnerGrid,
+        landing: &[Node],
+    ) -> Result<usize, InnerGridError> {
+        let blocked = || InnerGridError::FooterPushBlocked {
+            id: collided.id.to_string(),
+        };
+
+        let up_y = y
+            .checked_sub(collided.h + self.item_gap)
+            .ok_or_else(blocked)?;
+        let escaped = collided.with_position(collided.x, up_y);
+        if !self
+            .will_collides_with(collided, collided.x, up_y, grid)?
+            .is_empty()
+            || landing.iter().any(|node| node.intersects(&escaped))
+        {
+            return Err(blocked());
+        }
+
+        Ok(up_y)
+    }
+
+    /// Finds the first y coordinate, starting at `y`, where the node doesn't
+    /// collide with any item.
+    ///
+    /// Cells past the current bounds count as free, whether the grid can
+    /// actually grow there is checked when the node is placed.
+    fn next_free_y(&self, node: &Node, x: usize, mut y: usize) -> usize {
+        loop {
+            let blocked = (y..y + node.h).any(|cell_y| {
+                (x..x + node.w).any(|cell_x| {
+                    matches!(self.grid.peek(cell_x, cell_y), Some(Some(id)) if id != node.id)
+                })
+            });
+
+            if !blocked {
+                return y;
+            }
+            y += 1;
+        }
+    }
+
+    /// Finds the first y coordinate, starting at `y`, where the node doesn't
+    /// collide with any item it can't displace.
+    ///
+    /// Heavier items can't be displaced and, with [`CollisionStrategy::StackInColumn`],
+    /// neither can items in another lane.
+    fn settle_below_immovable(
+        &self,
+        node: &Node,
+        x: usize,
+        mut y: usize,
+        grid: &InnerGrid,
+    ) -> Result<usize, InnerGridError> {
+        let other_lane = |collided: &Node| {
+            self.collision_strategy == CollisionStrategy::StackInColumn
+                && (collided.x, collided.w) != (x, node.w)
+        };
+
+        loop {
+            let immovable_bottom = self
+                .will_collides_with(node, x, y, grid)?
+                .iter()
+                .filter_map(|collided| self.pending_node(&collided.id))
+                .filter(|collided| collided.weight > node.weight || other_lane(collided))
+                .map(|collided| collided.y + collided.h)
+                .max();
+
+            match immovable_bottom {
+                Some(bottom) => y = bottom + self.item_gap,
+                None => return Ok(y),
+            }
+        }
+    }
+
+    /// Returns a node as it will be once the pending changes are applied.
+    fn pending_node(&self, id: &str) -> Option<Node> {
+        self.pending_changes
+            .iter()
+            .rev()
+            .find_map(|change| match change {
+                Change::Move(data) if data.new_value.id == id => Some(data.new_value.clone()),
+                _ => None,
+            })
+            .or_else(|| self.items.get(id).cloned())
+    }
+
+    /// Creates a change operation to move a node to a new position.
+    ///
+    /// This method:
+    /// 1. Clears the node's current footprint from the working grid
+    /// 2. Handles any collisions at the new position
+    /// 3. Writes the node at its new position into the working grid
+    /// 4. Creates a Move change operation, or updates the one already scheduled
+    ///
+    /// The working grid is shared by the whole cascade, so items displaced
+    /// earlier are seen at their new position by the following collisions.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to move, as it currently stands in the working grid
+    /// * `new_x` - Target x coordinate
+    /// * `new_y` - Target y coordinate
+    /// * `grid` - The working grid to check for collisions
+    /// * `landing` - The nodes still being placed, see [`GridEngine::handle_collision`]
+    fn create_move_change(
+        &mut self,
+        node: Node,
+        new_x: usize,
+        new_y: usize,
+        grid: &mut InnerGrid,
+        landing: &mut Vec<Node>,
+    ) -> Result<(), InnerGridError> {
+        node.update_grid(grid, UpdateGridOperation::Remove)?;
+        let new_y = self.handle_collision(&node, new_x, new_y, grid, landing)?;
+
+        let moved = node.with_position(new_x, new_y);
+        moved.update_grid(grid, UpdateGridOperation::Add)?;
+
+        let scheduled = self
+            .pending_changes
+            .iter_mut()
+            .find_map(|change| match change {
+                Change::Move(data) if data.new_value.id == node.id => Some(data),
+                _ => None,
+            });
+
+        match scheduled {
+            Some(data) => data.new_value = moved,
+            None => self
+                .pending_changes
+                .push(Change::Move(MoveChangeData::new(node, moved))),
+        }
+
+        Ok(())
+    }
+
+    /// Finds the item to swap places with when moving a node.
+    ///
+    /// A swap only happens with [`CollisionStrategy::Swap`], when the node would
+    /// collide with a single item of the same size placed exactly at the target
+    /// position.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Node))` - The item that should take the moving node's origin
+    /// * `Ok(None)` - If the default collision handling should be used
+    /// * `Err(InnerGridError)` - If the collision check fails
+    fn swap_candidate(
+        &self,
+        node: &Node,
+        x: usize,
+        y: usize,
+        grid: &InnerGrid,
+    ) -> Result<Option<Node>, InnerGridError> {
+        if self.collision_strategy != CollisionStrategy::Swap {
+            return Ok(None);
+        }
+
+        let collides_with = self.will_collides_with(node, x, y, grid)?;
+
+        match collides_with.as_slice() {
+            [collided]
+                if collided.x == x
+                    && collided.y == y
+                    && collided.w == node.w
+                    && collided.h == node.h =>
+            {
+                Ok(Some((*collided).clone()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Moves an existing item to a new position in the grid.
+    ///
+    /// If the move would cause collisions, affected items are automatically
+    /// repositioned to prevent overlap, according to the current [`CollisionStrategy`].
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to move
+    /// * `new_x` - New X coordinate
+    /// * `new_y` - New Y coordinate
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(MoveOutcome))` - If move successful, with the items it displaced
+    /// * `Ok(None)` - If the item is already at the given position
+    /// * `Err(GridEngineError)` - If item doesn't exist or move invalid
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    ///
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1".to_string(), 0, 0, 2, 2)?;
+    /// grid.move_item("box1", 2, 2)?; // Moves box to position 2,2
+    ///
+    /// // Check if the item was moved correctly
+    /// let item = grid.get_nodes();
+    /// assert_eq!(item.len(), 1);
+    /// assert_eq!(item[0].x(), &2);
+    /// assert_eq!(item[0].y(), &2);
+    ///
+    /// # Ok(())
+    /// # }
+    ///
+    /// ```
+    pub fn move_item(
+        &mut self,
+        id: &str,
+        new_x: usize,
+        new_y: usize,
+    ) -> Result<Option<MoveOutcome>, GridEngineError> {
+        if let Err(error) = self.create_move_item_changes(id, new_x, new_y) {
+            self.pending_changes.clear();
+            return Err(error);
+        }
+
+        // Moving to the current position is a no-op, nothing to apply or notify
+        if self.pending_changes.is_empty() {
+            return Ok(None);
+        }
+
+        let changes = self.pending_changes.clone();
+        self.apply_changes(&changes)?;
+        self.pending_changes.clear();
+
+        let (moved, displaced): (Vec<Change>, Vec<Change>) =
+            changes.into_iter().partition(|change| match change {
+                Change::Move(data) => data.new_value.id == id,
+                Change::Resize(data) => data.new_value.id == id,
+                _ => false,
+            });
+
+        Ok(moved
+            .into_iter()
+            .next()
+            .map(|moved| MoveOutcome { moved, displaced }))
+    }
+
+    /// Moves an existing item to a new position, with typed coordinates.
+    ///
+    /// Same as [`GridEngine::move_item`], taking a [`Position`] or an `(x, y)` tuple.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to move
+    /// * `position` - New position of the item's top-left corner
+    pub fn move_item_to(
+        &mut self,
+        id: &str,
+        position: impl Into<Position>,
+    ) -> Result<Option<MoveOutcome>, GridEngineError> {
+        let position = position.into();
+        self.move_item(id, position.x, position.y)
+    }
+
+    /// Previews moving an item, without changing the grid.
+    ///
+    /// Runs the same collision resolution as [`GridEngine::move_item`] on a copy
+    /// of the engine and returns the changes the move would apply. Nothing is
+    /// applied and no listener is notified, which makes it suitable to show where
+    /// displaced items would land while an item is being dragged. Like other
+    /// queries, it works on a frozen engine too.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to move
+    /// * `x` - Target X coordinate
+    /// * `y` - Target Y coordinate
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Change>)` - The changes the move would apply, empty if the item
+    ///   is already at the given position
+    /// * `Err(GridEngineError)` - If the move would fail
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.add_item("box2", 0, 2, 2, 2)?;
+    ///
+    /// let changes = grid.preview_move("box1", 0, 2)?;
+    /// assert_eq!(changes.len(), 2);
+    /// assert_eq!(grid.get_nodes()[0].y(), &0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn preview_move(
+        &self,
+        id: &str,
+        x: usize,
+        y: usize,
+    ) -> Result<Vec<Change>, GridEngineError> {
+        let mut working = self.clone();
+        working.create_move_item_changes(id, x, y)?;
+
+        Ok(working.pending_changes)
+    }
+
+    /// Creates the change operations moving an item, resolving its collisions.
+    ///
+    /// No change is created when the item is already at the given position.
+    fn create_move_item_changes(
+        &mut self,
+        id: &str,
+        new_x: usize,
+        new_y: usize,
+    ) -> Result<(), GridEngineError> {
+        let node = match self.items.get(id) {
+            Some(node) => node,
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: id.to_string(),
+            }))?,
+        };
+
+        let (new_x, new_y) = self.snap_position(node, new_x, new_y);
+        if node.x == new_x && node.y == new_y {
+            return Ok(());
+        }
+
+        let node = node.clone();
+        let mut grid = self.grid.clone();
+
+        node.update_grid(&mut grid, UpdateGridOperation::Remove)?;
+        if let Some(h) = self.shrunk_height(&node, new_x, new_y, &grid)? {
+            let resized = Node {
+                h,
+                ..node.with_position(new_x, new_y)
+            };
+            self.pending_changes
+                .push(Change::Resize(ResizeChangeData::new(node, resized)));
+            return Ok(());
+        }
+
+        match self.swap_candidate(&node, new_x, new_y, &grid)? {
+            Some(collided) => {
+                let swapped = collided.with_position(node.x, node.y);
+                self.pending_changes
+                    .push(Change::Move(MoveChangeData::new(collided, swapped)));
+
+                let moved = node.with_position(new_x, new_y);
+                self.pending_changes
+                    .push(Change::Move(MoveChangeData::new(node, moved)));
+            }
+            None => self.create_move_change(node, new_x, new_y, &mut grid, &mut Vec::new())?,
+        }
+
+        Ok(())
+    }
+
+    /// Clamps a move target so the node stays within the bounds, when
+    /// [`GridEngine::set_snap_to_bounds`] is enabled.
+    fn snap_position(&self, node: &Node, x: usize, y: usize) -> (usize, usize) {
+        if !self.snap_to_bounds {
+            return (x, y);
+        }
+
+        let x = if self.grid.can_expand_x() {
+            x
+        } else {
+            x.min(self.grid.cols().saturating_sub(node.w))
+        };
+        let row_limit = if self.grid.can_expand_y() {
+            self.grid.max_rows()
+        } else {
+            Some(self.grid.rows())
+        };
+        let y = row_limit.map_or(y, |rows| y.min(rows.saturating_sub(node.h)));
+
+        (x, y)
+    }
+
+    /// Finds the height an incoming node shrinks to, with
+    /// [`CollisionStrategy::ShrinkToFit`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(usize))` - The tallest height, below the node's own, that
+    ///   fits at the target without colliding or leaving the grid
+    /// * `Ok(None)` - If the node fits as is, nothing fits or the strategy is
+    ///   another one
+    /// * `Err(InnerGridError)` - If the collision check fails
+    fn shrunk_height(
+        &self,
+        node: &Node,
+        x: usize,
+        y: usize,
+        grid: &InnerGrid,
+    ) -> Result<Option<usize>, InnerGridError> {
+        if self.collision_strategy != CollisionStrategy::ShrinkToFit {
+            return Ok(None);
+        }
+
+        let fits = |h: usize| {
+            colliding_ids(
+                grid,
+                Some(&node.id),
+                Position::new(x, y),
+                Size::new(node.w, h),
+            )
+            .is_ok_and(|ids| ids.is_empty())
+        };
+        if fits(node.h) {
+            return Ok(None);
+        }
+
+        Ok((1..node.h).rev().find(|&h| fits(h)))
+    }
+
+    /// Moves a group of items at once, as if they were picked up together.
+    ///
+    /// Every listed item is lifted from the grid before any of them is placed,
+    /// so items in the group never push each other. Items outside the group that
+    /// overlap a target are displaced according to the current
+    /// [`CollisionStrategy`], but never onto a group item. Listeners are notified
+    /// with a single event holding the net moves.
+    ///
+    /// # Arguments
+    ///
+    /// * `moves` - The `(id, x, y)` target of each item in the group
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Change>)` - The applied changes
+    /// * `Err(GridEngineError)` - If an item doesn't exist, two targets overlap
+    ///   or placement fails, in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.add_item("box2", 2, 0, 2, 2)?;
+    ///
+    /// // Moving the pair one step right, box1 lands where box2 was
+    /// grid.move_many(&[("box1", 1, 0), ("box2", 3, 0)])?;
+    /// assert_eq!(grid.get_nodes()[0].x(), &1);
+    /// assert_eq!(grid.get_nodes()[1].x(), &3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn move_many(
+        &mut self,
+        moves: &[(&str, usize, usize)],
+    ) -> Result<Vec<Change>, GridEngineError> {
+        let mut targets: Vec<Node> = Vec::with_capacity(moves.len());
+        for (id, x, y) in moves {
+            let node = match self.items.get(*id) {
+                Some(node) => node.with_position(*x, *y),
+                None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                    id: id.to_string(),
+                }))?,
+            };
+
+            let overlapping = targets
+                .iter()
+                .filter(|other| other.id != node.id && other.intersects(&node))
+                .map(|other| other.id.to_string())
+                .collect::<Vec<String>>();
+            if !overlapping.is_empty() {
+                return Err(GridEngineError::Item(ItemError::PositionOccupied {
+                    ids: overlapping,
+                }));
+            }
+
+            targets.retain(|other| other.id != node.id);
+            targets.push(node);
+        }
+
+        targets.sort_by_key(|node| (node.y, node.x));
+        self.apply_on_working_copy(|working| {
+            for node in &targets {
+                let current = &working.items[&node.id];
+                current.update_grid(&mut working.grid, UpdateGridOperation::Remove)?;
+                working.items.remove(&node.id);
+            }
+
+            // Group items are pinned so they can't displace each other
+            for node in &targets {
+                let pinned = Node {
+                    weight: u32::MAX,
+                    ..node.clone()
+                };
+                working.handle_collision(
+                    &pinned,
+                    node.x,
+                    node.y,
+                    &mut working.grid.clone(),
+                    &mut Vec::new(),
+                )?;
+                working.create_add_change(pinned);
+                working.apply_changes(&working.pending_changes.clone())?;
+                working.pending_changes.clear();
+            }
+
+            for node in targets {
+                working.items.insert(node.id.to_string(), node);
+            }
+            Ok(())
+        })
+    }
+
+    /// Moves an existing item to a new position only if the target area is free.
+    ///
+    /// Unlike [`GridEngine::move_item`], other items are never displaced. The
+    /// item's own current footprint doesn't count as occupied, so it can be
+    /// moved onto an area overlapping its current position.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to move
+    /// * `new_x` - New X coordinate
+    /// * `new_y` - New Y coordinate
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the item was moved, or is already at the given position
+    /// * `Err(GridEngineError)` - If the item doesn't exist, or the target area is
+    ///   occupied by other items, in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.add_item("box2", 4, 0, 2, 2)?;
+    ///
+    /// assert!(grid.move_item_strict("box1", 3, 0).is_err());
+    /// grid.move_item_strict("box1", 1, 0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn move_item_strict(
+        &mut self,
+        id: &str,
+        new_x: usize,
+        new_y: usize,
+    ) -> Result<(), GridEngineError> {
+        let node = match self.items.get(id) {
+            Some(node) => node.clone(),
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: id.to_string(),
+            }))?,
+        };
+
+        if node.x == new_x && node.y == new_y {
+            return Ok(());
+        }
+
+        let blockers = self
+            .will_collides_with(&node, new_x, new_y, &self.grid)?
+            .iter()
+            .map(|n| n.id.to_string())
+            .collect::<Vec<String>>();
+
+        if !blockers.is_empty() {
+            return Err(GridEngineError::Item(ItemError::PositionOccupied {
+                ids: blockers,
+            }));
+        }
+
+        let moved = node.with_position(new_x, new_y);
+        self.pending_changes
+            .push(Change::Move(MoveChangeData::new(node, moved)));
+
+        self.apply_changes(&self.pending_changes.clone())?;
+        self.pending_changes.clear();
+
+        Ok(())
+    }
+
+    /// Changes the size of an existing item, keeping its top-left corner.
+    ///
+    /// Items overlapping the new footprint are displaced according to the current
+    /// [`CollisionStrategy`]. Listeners are notified with a [`Change::Resize`]
+    /// holding the old and new dimensions, along with any displacement.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to resize
+    /// * `w` - New width
+    /// * `h` - New height
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the item was resized, or already has the given size
+    /// * `Err(GridEngineError)` - If the item doesn't exist, or the new size is
+    ///   empty or doesn't fit the grid, in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// grid.add_item("box2", 0, 2, 2, 2)?;
+    ///
+    /// grid.resize_item("box1", 3, 3)?;
+    /// assert_eq!(grid.get_nodes()[0].w(), &3);
+    /// // Pushed below the grown item
+    /// assert_eq!(grid.get_nodes()[1].y(), &3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resize_item(&mut self, id: &str, w: usize, h: usize) -> Result<(), GridEngineError> {
+        let current = match self.items.get(id) {
+            Some(node) => node.clone(),
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: id.to_string(),
+            }))?,
+        };
+
+        self.check_dimensions(w, h)?;
+
+        if current.w == w && current.h == h {
+            return Ok(());
+        }
+
+        let resized = Node {
+            w,
+            h,
+            ..current.clone()
+        };
+        let changes = self.create_resize_change(current, resized)?;
+        self.apply_changes(&changes)?;
+
+        Ok(())
+    }
+
+    /// Creates the changes replacing a node with a differently sized version.
+    ///
+    /// The current footprint is cleared before resolving the collisions of the
+    /// new one, so the node never collides with itself. The displacements are
+    /// taken out of the pending changes even when the cascade fails halfway,
+    /// so nothing is left behind for the next operation.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Change>)` - The displacements followed by the resize
+    /// * `Err(InnerGridError)` - If resolving the collisions fails
+    fn create_resize_change(
+        &mut self,
+        current: Node,
+        target: Node,
+    ) -> Result<Vec<Change>, InnerGridError> {
+        let mut grid = self.grid.clone();
+        current.update_grid(&mut grid, UpdateGridOperation::Remove)?;
+
+        let resolved =
+            self.handle_collision(&target, target.x, target.y, &mut grid, &mut Vec::new());
+        let mut changes = std::mem::take(&mut self.pending_changes);
+        let y = resolved?;
+        changes.push(Change::Resize(ResizeChangeData::new(
+            current,
+            target.with_position(target.x, y),
+        )));
+
+        Ok(changes)
+    }
+
+    /// Sets the collision weight of an item.
+    ///
+    /// When items collide, heavier items stay in place while lighter ones are
+    /// displaced. Items added to the grid start with a weight of 1.
+    ///
+    /// Like any other change to an item, listeners are notified, here with a
+    /// [`Change::Reorder`] since the item stays in place, and can veto it.
+    /// Nothing happens if the weight is unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item
+    /// * `weight` - The new weight
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the weight was updated
+    /// * `Err(GridEngineError)` - If item doesn't exist, the engine is frozen or
+    ///   the change was vetoed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("header", 0, 0, 4, 1)?;
+    /// grid.set_item_weight("header", 10)?;
+    ///
+    /// // The lighter item is placed below the header instead of pushing it
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// assert_eq!(grid.get_nodes()[0].y(), &1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_item_weight(&mut self, id: &str, weight: u32) -> Result<(), GridEngineError> {
+        if self.frozen {
+            return Err(GridEngineError::Frozen);
+        }
+
+        let current = match self.items.get(id) {
+            Some(node) => node.clone(),
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: id.to_string(),
+            }))?,
+        };
+        if current.weight == weight {
+            return Ok(());
+        }
+
+        let updated = Node {
+            weight,
+            ..current.clone()
+        };
+        self.apply_changes(&[Change::Reorder(ReorderChangeData::new(current, updated))])
+    }
+
+    /// Saves the current state of a single item.
+    ///
+    /// The returned node can later be passed to [`GridEngine::restore_item_state`]
+    /// to put the item back where it was, e.g. to undo a single widget's move.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to save
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Node)` - A copy of the item's current state
+    /// * `None` - If the item doesn't exist
+    pub fn save_item_state(&self, id: &str) -> Option<Node> {
+        self.items.get(id).cloned()
+    }
+
+    /// Restores an item to a previously saved state.
+    ///
+    /// The item is moved, and resized if needed, back to the saved geometry.
+    /// Collisions at the restored position are resolved like any other placement.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The state returned by [`GridEngine::save_item_state`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the item was restored
+    /// * `Err(GridEngineError)` - If the item no longer exists or placement fails
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// let saved = grid.save_item_state("box1").unwrap();
+    /// grid.move_item("box1", 4, 4)?;
+    /// grid.restore_item_state(saved)?;
+    ///
+    /// assert_eq!(grid.get_nodes()[0].x(), &0);
+    /// assert_eq!(grid.get_nodes()[0].y(), &0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore_item_state(&mut self, node: Node) -> Result<(), GridEngineError> {
+        let current = match self.items.get(&node.id) {
+            Some(current) => current.clone(),
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: node.id.to_string(),
+            }))?,
+        };
+
+        if current.w == node.w && current.h == node.h {
+            self.move_item(&node.id, node.x, node.y)?;
+            return Ok(());
+        }
+
+        let changes = self.create_resize_change(current, node)?;
+        self.apply_changes(&changes)?;
+
+        Ok(())
+    }
+
+    /// Moves an existing item relative to its current position.
+    ///
+    /// The destination is clamped so the item never goes past the top or left
+    /// edges, nor past the right edge of the grid. The move itself is delegated
+    /// to [`GridEngine::move_item`], so collisions are handled as usual.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the item to move
+    /// * `dx` - Offset to apply on the x axis
+    /// * `dy` - Offset to apply on the y axis
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((usize, usize))` - The clamped destination of the item
+    /// * `Err(GridEngineError)` - If item doesn't exist or move invalid
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// assert_eq!(grid.move_item_by("box1", -1, 3)?, (0, 3));
+    /// assert_eq!(grid.move_item_by("box1", 20, 0)?, (8, 3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn move_item_by(
+        &mut self,
+        id: &str,
+        dx: isize,
+        dy: isize,
+    ) -> Result<(usize, usize), GridEngineError> {
+        let node = match self.items.get(id) {
+            Some(node) => node,
+            None => Err(GridEngineError::Item(ItemError::ItemNotFound {
+                id: id.to_string(),
+            }))?,
+        };
+
+        let max_x = self.grid.cols().saturating_sub(node.w);
+        let new_x = node.x.saturating_add_signed(dx).min(max_x);
+        let new_y = node.y.saturating_add_signed(dy);
+
+        self.move_item(id, new_x, new_y)?;
+
+        Ok((new_x, new_y))
+    }
+
+    /// Snaps every item to a coarser sub-grid.
+    ///
+    /// Each item's x and y are rounded down to the nearest multiple of
+    /// `pitch_x` and `pitch_y`, visiting items top to bottom then left to right.
+    /// Collisions caused by snapping are resolved with the current collision
+    /// strategy. Items already aligned stay put unless displaced by another one,
+    /// and listeners are notified with a single event holding the net moves.
+    /// A pitch of 0 is treated as 1, leaving that axis untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `pitch_x` - Horizontal pitch of the sub-grid
+    /// * `pitch_y` - Vertical pitch of the sub-grid
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the items were aligned
+    /// * `Err(GridEngineError)` - If resolving the collisions fails, in which case
+    ///   nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 3, 5, 2, 2)?;
+    ///
+    /// grid.align_to_grid(2, 2)?;
+    /// assert_eq!(grid.get_nodes()[0].x(), &2);
+    /// assert_eq!(grid.get_nodes()[0].y(), &4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn align_to_grid(&mut self, pitch_x: usize, pitch_y: usize) -> Result<(), GridEngineError> {
+        let (pitch_x, pitch_y) = (pitch_x.max(1), pitch_y.max(1));
+
+        let mut nodes: Vec<&Node> = self.items.values().collect();
+        nodes.sort_by_key(|node| (node.y, node.x));
+        let ids: Vec<String> = nodes.iter().map(|node| node.id.to_string()).collect();
+
+        self.apply_on_working_copy(|working| {
+            for id in ids {
+                let node = &working.items[&id];
+                let (x, y) = (node.x - node.x % pitch_x, node.y - node.y % pitch_y);
+                working.move_item(&id, x, y)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Pulls every item left as far as it goes without colliding.
+    ///
+    /// Items are visited left to right then top to bottom, so each one slides
+    /// into the room freed by the items before it. Listeners are notified with a
+    /// single event holding one move per item that slid. Compacting an already
+    /// compact grid changes nothing.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the items were compacted
+    /// * `Err(GridEngineError)` - If applying the moves fails, in which case
+    ///   nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 3, 0, 2, 2)?;
+    ///
+    /// grid.compact_horizontal()?;
+    /// assert_eq!(grid.get_nodes()[0].x(), &0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compact_horizontal(&mut self) -> Result<(), GridEngineError> {
+        let mut nodes: Vec<&Node> = self.items.values().collect();
+        nodes.sort_by_key(|node| (node.x, node.y));
+        let ids: Vec<String> = nodes.iter().map(|node| node.id.to_string()).collect();
+
+        self.apply_on_working_copy(|working| {
+            for id in ids {
+                let node = &working.items[&id];
+                let column_is_free = |x: usize| {
+                    (node.y..node.y + node.h).all(
+                        |y| !matches!(working.grid.peek(x, y), Some(Some(cell)) if cell != node.id),
+                    )
+                };
+
+                let mut x = node.x;
+                while x > 0 && column_is_free(x - 1) {
+                    x -= 1;
+                }
+                if x > 0 {
+                    // Stopped by a neighbour rather than the edge
+                    x = (x + working.item_gap).min(node.x);
+                }
+
+                let y = node.y;
+                working.move_item(&id, x, y)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Re-packs every item as close to the top-left corner as it fits.
+    ///
+    /// All items are lifted off the grid and placed back one at a time, biggest
+    /// area first and then in creation order, each at the first free spot found
+    /// scanning rows top to bottom and columns left to right. Unlike
+    /// [`GridEngine::compact_horizontal`], items don't keep their relative
+    /// order, so this can change the position of any item. Listeners are
+    /// notified with a single event holding one move per relocated item.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the items were re-packed
+    /// * `Err(GridEngineError)` - If an item finds no room within the row
+    ///   limit or applying the moves fails, in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 4);
+    /// grid.add_item("small", 3, 5, 1, 1)?;
+    /// grid.add_item("big", 1, 7, 2, 2)?;
+    ///
+    /// grid.defragment()?;
+    /// assert_eq!(grid.get_nodes()[0].id(), "big");
+    /// assert_eq!((grid.get_nodes()[0].x(), grid.get_nodes()[0].y()), (&0, &0));
+    /// assert_eq!((grid.get_nodes()[1].x(), grid.get_nodes()[1].y()), (&2, &0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn defragment(&mut self) -> Result<(), GridEngineError> {
+        let mut nodes: Vec<Node> = self.items.values().cloned().collect();
+        nodes.sort_by_key(|node| (std::cmp::Reverse(node.area()), node.seq, node.id.clone()));
+
+        self.apply_on_working_copy(|working| {
+            for node in &nodes {
+                node.update_grid(&mut working.grid, UpdateGridOperation::Remove)?;
+            }
+
+            for node in nodes {
+                let spot = first_fit(&working.grid, node.size())?;
+                let placed = node.with_position(spot.x, spot.y);
+                placed.update_grid(&mut working.grid, UpdateGridOperation::Add)?;
+                working.items.insert(placed.id.clone(), placed);
+            }
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Inserts an empty row at `at`, shifting every item at or below it down by one.
+    ///
+    /// The grid grows by one row and listeners are notified with a single event
+    /// holding one move per shifted item, no event is sent when nothing moves.
+    /// Items straddling the boundary, starting above `at` and extending past it,
+    /// can't be split, so the insertion is rejected instead of resizing them.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` - Index of the new row, up to the current number of rows
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the row was inserted
+    /// * `Err(GridEngineError)` - If `at` is past the last row, the grid can't
+    ///   grow vertically or an item straddles it, in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 4);
+    /// grid.add_item("box1", 0, 1, 2, 2)?;
+    ///
+    /// grid.insert_row(0)?;
+    /// assert_eq!(grid.get_nodes()[0].y(), &2);
+    /// assert_eq!(grid.get_inner_grid().rows(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert_row(&mut self, at: usize) -> Result<(), GridEngineError> {
+        if self.frozen {
+            return Err(GridEngineError::Frozen);
+        }
+
+        let rows = self.grid.rows();
+        let can_grow =
+            self.grid.can_expand_y() && self.grid.max_rows().is_none_or(|max_rows| rows < max_rows);
+        if at > rows || !can_grow {
+            return Err(InnerGridError::OutOfBoundsAccess {
+                x: 0,
+                y: at.max(rows),
+                axis: Axis::Y,
+            }
+            .into());
+        }
+        self.check_no_straddler(at)?;
+
+        // Bottom items first, so each one moves into already vacated cells
+        let mut shifted: Vec<Node> = self
+            .items
+            .values()
+            .filter(|node| node.y >= at)
+            .cloned()
+            .collect();
+        shifted.sort_by_key(|node| std::cmp::Reverse(node.y));
+
+        let changes: Vec<Change> = shifted
+            .into_iter()
+            .map(|node| {
+                let moved = node.with_position(node.x, node.y + 1);
+                Change::Move(MoveChangeData::new(node, moved))
+            })
+            .collect();
+
+        self.grid.expand_rows(1);
+        if changes.is_empty() {
+            return Ok(());
+        }
+        if let Err(err) = self.apply_changes(&changes) {
+            self.grid.truncate_rows(rows);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the row at `at`, shifting every item below it up by one.
+    ///
+    /// Items fully contained in the row are removed, and the grid shrinks by one
+    /// row, never below [`GridEngine::min_rows`]. Listeners are notified with a
+    /// single event holding the removals and the moves, no event is sent when
+    /// nothing changes. Items straddling the row, covering it and at least one more row,
+    /// can't be shrunk, so the deletion is rejected instead of resizing them.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` - Index of the row to delete
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Node>)` - The items removed along with the row
+    /// * `Err(GridEngineError)` - If `at` is outside the grid or an item straddles
+    ///   it, in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(4, 4);
+    /// grid.add_item("header", 0, 0, 4, 1)?;
+    /// grid.add_item("box1", 0, 1, 2, 2)?;
+    ///
+    /// let removed = grid.delete_row(0)?;
+    /// assert_eq!(removed[0].id(), "header");
+    /// assert_eq!(grid.get_nodes()[0].y(), &0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn delete_row(&mut self, at: usize) -> Result<Vec<Node>, GridEngineError> {
+        if self.frozen {
+            return Err(GridEngineError::Frozen);
+        }
+
+        let rows = self.grid.rows();
+        if at >= rows {
+            return Err(InnerGridError::OutOfBoundsAccess {
+                x: 0,
+                y: at,
+                axis: Axis::Y,
+            }
+            .into());
+        }
+        self.check_no_straddler(at)?;
+        if let Some(node) = self.items.values().find(|node| node.y == at && node.h > 1) {
+            return Err(ItemError::StraddlesRow {
+                id: node.id.to_string(),
+                row: at,
+            }
+            .into());
+        }
+
+        let removed: Vec<Node> = self
+            .items
+            .values()
+            .filter(|node| node.y == at)
+            .cloned()
+            .collect();
+        for node in &removed {
+            self.create_remove_change(node);
+        }
+
+        // Top items first, so each one moves into already vacated cells
+        let mut shifted: Vec<Node> = self
+            .items
+            .values()
+            .filter(|node| node.y > at)
+            .cloned()
+            .collect();
+        shifted.sort_by_key(|node| node.y);
+
+        for node in shifted {
+            let moved = node.with_position(node.x, node.y - 1);
+            self.pending_changes
+                .push(Change::Move(MoveChangeData::new(node, moved)));
+        }
+
+        if !self.pending_changes.is_empty() {
+            self.apply_changes(&self.pending_changes.clone())?;
+            self.pending_changes.clear();
+        }
+        self.grid.truncate_rows((rows - 1).max(self.min_rows));
+
+        Ok(removed)
+    }
+
+    /// Fails if an item starts above row `at` and extends into it.
+    fn check_no_straddler(&self, at: usize) -> Result<(), ItemError> {
+        match self
+            .items
+            .values()
+            .find(|node| node.y < at && node.y + node.h > at)
+        {
+            Some(node) => Err(ItemError::StraddlesRow {
+                id: node.id.to_string(),
+                row: at,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Applies a batch of changes to the grid.
+    ///
+    /// This method handles the actual application of all pending changes to both
+    /// the grid structure and the item tracking system. Changes are applied in order,
+    /// and all operations are executed atomically - if any change fails, none of
+    /// the changes will be applied.
+    ///
+    /// Before anything is applied, pre-change listeners are consulted and may veto
+    /// the whole batch, in which case the pending changes are discarded. The same
+    /// happens, without consulting the listeners, when the engine is frozen.
+    ///
+    /// After successful application, triggers change events to notify any registered listeners.
+    ///
+    /// In debug builds, or with the `strict-invariants` feature, the grid is
+    /// checked with [`GridEngine::validate`] once the changes are committed,
+    /// panicking on any inconsistency.
+    ///
+    /// # Arguments
+    ///
+    /// * `changes` - Vector of changes to apply (Add, Remove, or Move operations)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If all changes were applied successfully
+    /// * `Err(GridEngineError)` - If the engine is frozen, the changes were vetoed
+    ///   or any change application fails
+    fn apply_changes(&mut self, changes: &[Change]) -> Result<(), GridEngineError> {
+        if self.frozen {
+            self.pending_changes.clear();
+            return Err(GridEngineError::Frozen);
+        }
+
+        let event = ChangesEventValue::new(changes.to_vec());
+        if !self.events.trigger_pre_changes_event(&event) {
+            self.pending_changes.clear();
+            return Err(GridEngineError::ChangesVetoed);
+        }
+
+        // Changes are applied to copies so a failure leaves the engine untouched
+        let mut grid = self.grid.clone();
+        let mut items = self.items.clone();
+
+        if let Err(err) = apply_changes_to(&mut grid, &mut items, changes) {
+            self.pending_changes.clear();
+            return Err(err.into());
+        }
+
+        if self.collision_strategy == CollisionStrategy::Stack || !self.stacked.is_empty() {
+            match restack(&mut grid, &items) {
+                Ok(stacked) => self.stacked = stacked,
+                Err(err) => {
+                    self.pending_changes.clear();
+                    return Err(err.into());
+                }
+            }
+        }
+
+        self.grid = grid;
+        self.items = items;
+
+        // Items added or reordered from outside keep their own sequence numbers
+        for change in changes {
+            let seq = match change {
+                Change::Add(data) => data.value.seq,
+                Change::Reorder(data) => data.new_value.seq,
+                _ => continue,
+            };
+            self.next_seq = self.next_seq.max(seq + 1);
+        }
+
+        #[cfg(any(debug_assertions, feature = "strict-invariants"))]
+        if let Err(reports) = self.validate() {
+            panic!("Grid invariants violated after applying changes: {reports:?}");
+        }
+
+        self.events.trigger_changes_event(&event);
+        Ok(())
+    }
+
+    /// Applies changes computed by another engine, e.g. a remote peer.
+    ///
+    /// Changes are validated in order against the local state before anything
+    /// is applied: adding an existing id, or removing or moving a missing one,
+    /// is rejected. Moves must also start from the item's current local state.
+    /// No collision resolution is performed, the changes are expected to come
+    /// from an engine that already resolved them: an item added, moved or
+    /// resized onto another one, as the batch leaves them, is rejected, unless
+    /// items may overlap with [`CollisionStrategy::Stack`].
+    ///
+    /// On success, listeners are notified with a single change event.
+    ///
+    /// # Arguments
+    ///
+    /// * `changes` - The changes to apply, in order
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If all changes were applied
+    /// * `Err(GridEngineError)` - If any change is invalid, would make items
+    ///   overlap or fails to apply, in which case nothing is changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// use std::sync::{Arc, Mutex};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut peer = GridEngine::new(10, 10);
+    /// let received = Arc::new(Mutex::new(Vec::new()));
+    /// let received_clone = received.clone();
+    /// peer.events_mut().add_changes_listener(move |event| {
+    ///     received_clone.lock().unwrap().extend(event.changes().clone());
+    /// })?;
+    /// peer.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// let mut local = GridEngine::new(10, 10);
+    /// local.apply_external(received.lock().unwrap().clone())?;
+    /// assert_eq!(local.get_nodes(), peer.get_nodes());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn apply_external(&mut self, changes: Vec<Change>) -> Result<(), GridEngineError> {
+        let mut items = self.items.clone();
+        let mut placed: Vec<&str> = Vec::new();
+
+        for change in changes.iter() {
+            match change {
+                Change::Add(data) => {
+                    let id = &data.value.id;
+                    if items.contains_key(id) {
+                        return Err(GridEngineError::Item(ItemError::ItemAlreadyExists {
+                            id: id.to_string(),
+                        }));
+                    }
+                    if data.value.w == 0 || data.value.h == 0 {
+                        return Err(GridEngineError::Item(ItemError::InvalidDimensions {
+                            w: data.value.w,
+                            h: data.value.h,
+                        }));
+                    }
+                    items.insert(id.to_string(), data.value.clone());
+                    placed.push(id);
+                }
+                Change::Remove(data) => {
+                    let id = &data.value.id;
+                    if items.remove(id).is_none() {
+                        return Err(GridEngineError::Item(ItemError::ItemNotFound {
+                            id: id.to_string(),
+                        }));
+                    }
+                }
+                Change::Move(MoveChangeData {
+                    old_value,
+                    new_value,
+                })
+                | Change::Resize(ResizeChangeData {
+                    old_value,
+                    new_value,
+                })
+                | Change::Reorder(ReorderChangeData {
+                    old_value,
+                    new_value,
+                }) => {
+                    let id = &old_value.id;
+                    match items.get(id) {
+                        None => {
+                            return Err(GridEngineError::Item(ItemError::ItemNotFound {
+                                id: id.to_string(),
+                            }));
+                        }
+                        Some(current) if current != old_value || new_value.id != *id => {
+                            return Err(GridEngineError::InnerGrid(
+                                InnerGridError::MismatchedGridItem { id: id.to_string() },
+                            ));
+                        }
+                        Some(_) if new_value.w == 0 || new_value.h == 0 => {
+                            return Err(GridEngineError::Item(ItemError::InvalidDimensions {
+                                w: new_value.w,
+                                h: new_value.h,
+                            }));
+                        }
+                        Some(_) => {
+                            items.insert(id.to_string(), new_value.clone());
+                            if !matches!(change, Change::Reorder(_)) {
+                                placed.push(id);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Only the final layout matters, items moved out of the way in the
+        // same batch don't count
+        if self.collision_strategy != CollisionStrategy::Stack {
+            for id in placed {
+                let Some(node) = items.get(id) else {
+                    continue;
+                };
+                let ids: Vec<String> = items
+                    .values()
+                    .filter(|other| other.id != node.id && other.intersects(node))
+                    .map(|other| other.id.to_string())
+                    .collect();
+                if !ids.is_empty() {
+                    return Err(GridEngineError::Item(ItemError::PositionOccupied { ids }));
+                }
+            }
+        }
+
+        self.apply_changes(&changes)
+    }
+
+    /// Takes a checkpoint of the current layout.
+    ///
+    /// The snapshot can later be handed to [`GridEngine::restore`] to bring the
+    /// grid back to this exact state, e.g. when a speculative drag is cancelled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    ///
+    /// let snapshot = grid.snapshot();
+    /// grid.move_item("box1", 4, 4)?;
+    /// grid.restore(snapshot)?;
+    ///
+    /// assert_eq!(grid.get_nodes()[0].x(), &0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> GridSnapshot {
+        GridSnapshot {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            grid: self.grid.clone(),
+            items: self.items.clone(),
+            collision_strategy: self.collision_strategy,
+            add_placement: self.add_placement,
+            item_gap: self.item_gap,
+            snap_to_bounds: self.snap_to_bounds,
+            footer_row: self.footer_row,
+            row_heights: self.row_heights.clone(),
+            col_widths: self.col_widths.clone(),
+        }
+    }
+
+    /// Restores the layout and configuration from a previously taken snapshot.
+    ///
+    /// Listeners are notified with a single change event describing the
+    /// difference between the current layout and the restored one, and
+    /// pre-change listeners may veto it like any other change. No event is
+    /// fired when the layout is unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The checkpoint returned by [`GridEngine::snapshot`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the snapshot was restored
+    /// * `Err(GridEngineError)` - If the engine is frozen, the changes were
+    ///   vetoed or the snapshot's items can't be painted back, in which case
+    ///   nothing is changed
+    pub fn restore(&mut self, snapshot: GridSnapshot) -> Result<(), GridEngineError> {
+        if self.frozen {
+            return Err(GridEngineError::Frozen);
+        }
+
+        let event = ChangesEventValue::new(diff_items(&self.items, &snapshot.items));
+        if !event.changes().is_empty() && !self.events.trigger_pre_changes_event(&event) {
+            return Err(GridEngineError::ChangesVetoed);
+        }
+
+        // Overlapping items are painted back in stacking order, anything else
+        // is restored cell for cell
+        let mut grid = snapshot.grid;
+        let mut stacked_grid = grid.clone();
+        let stacked = restack(&mut stacked_grid, &snapshot.items)?;
+        if !stacked.is_empty() {
+            grid = stacked_grid;
+        }
+
+        self.grid = grid;
+        self.items = snapshot.items;
+        self.stacked = stacked;
+        self.collision_strategy = snapshot.collision_strategy;
+        self.add_placement = snapshot.add_placement;
+        self.item_gap = snapshot.item_gap;
+        self.snap_to_bounds = snapshot.snap_to_bounds;
+        self.footer_row = snapshot.footer_row;
+        self.row_heights = snapshot.row_heights;
+        self.col_widths = snapshot.col_widths;
+        self.pending_changes.clear();
+        if let Some(seq) = self.items.values().map(|node| node.seq).max() {
+            self.next_seq = self.next_seq.max(seq + 1);
+        }
+
+        #[cfg(any(debug_assertions, feature = "strict-invariants"))]
+        if let Err(reports) = self.validate() {
+            panic!("Grid invariants violated after restoring a snapshot: {reports:?}");
+        }
+
+        if !event.changes().is_empty() {
+            self.events.trigger_changes_event(&event);
+        }
+        Ok(())
+    }
+
+    /// Registers a new change event listener.
+    ///
+    /// Shorthand for [`GridEvents::add_changes_listener`] on [`GridEngine::events_mut`],
+    /// reporting failures as a [`GridEngineError`].
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The callback function to execute when changes occur
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The listener id, used to remove it later
+    /// * `Err(GridEngineError::GridEvent)` - If the listener couldn't be registered
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// let listener_id = grid.add_changes_listener(|event| {
+    ///     println!("Changes occurred: {:?}", event.changes());
+    /// })?;
+    ///
+    /// assert!(grid.events_mut().remove_changes_listener(&listener_id).is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_changes_listener(
+        &mut self,
+        function: impl Fn(&ChangesEventValue) + Send + 'static + Sync,
+    ) -> Result<String, GridEngineError> {
+        Ok(self.events.add_changes_listener(function)?)
+    }
+
+    /// Registers a new change event listener under a chosen id.
+    ///
+    /// Shorthand for [`GridEvents::add_named_listener`] on [`GridEngine::events_mut`],
+    /// reporting failures as a [`GridEngineError`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The id of the listener, unique among all listeners
+    /// * `function` - The callback function to execute when changes occur
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The listener id, used to remove it later
+    /// * `Err(GridEngineError::GridEvent)` - If a listener already uses the id
+    pub fn add_named_listener(
+        &mut self,
+        name: impl Into<String>,
+        function: impl Fn(&ChangesEventValue) + Send + 'static + Sync,
+    ) -> Result<String, GridEngineError> {
+        Ok(self.events.add_named_listener(name, function)?)
+    }
+
+    /// Registers a new pre-change event listener.
+    ///
+    /// Shorthand for [`GridEvents::add_pre_change_listener`] on [`GridEngine::events_mut`],
+    /// reporting failures as a [`GridEngineError`].
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The callback function deciding whether the changes may be applied
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The listener id, used to remove it later
+    /// * `Err(GridEngineError::GridEvent)` - If the listener couldn't be registered
+    pub fn add_pre_change_listener(
+        &mut self,
+        function: impl Fn(&ChangesEventValue) -> bool + Send + 'static + Sync,
+    ) -> Result<String, GridEngineError> {
+        Ok(self.events.add_pre_change_listener(function)?)
+    }
+
+    /// Registers a listener called once for every item added.
+    ///
+    /// Wraps [`GridEngine::add_changes_listener`], skipping the other kinds of
+    /// changes in each batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The callback function, receiving the added item
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The listener id, used to remove it later
+    /// * `Err(GridEngineError::GridEvent)` - If the listener couldn't be registered
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.on_add(|node| println!("Added {}", node.id()))?;
+    ///
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_add(
+        &mut self,
+        function: impl Fn(&Node) + Send + 'static + Sync,
+    ) -> Result<String, GridEngineError> {
+        self.add_changes_listener(move |event| {
+            for change in event.changes() {
+                if let Change::Add(data) = change {
+                    function(&data.value);
+                }
+            }
+        })
+    }
+
+    /// Registers a listener called once for every item removed.
+    ///
+    /// Wraps [`GridEngine::add_changes_listener`], skipping the other kinds of
+    /// changes in each batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The callback function, receiving the removed item
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The listener id, used to remove it later
+    /// * `Err(GridEngineError::GridEvent)` - If the listener couldn't be registered
+    pub fn on_remove(
+        &mut self,
+        function: impl Fn(&Node) + Send + 'static + Sync,
+    ) -> Result<String, GridEngineError> {
+        self.add_changes_listener(move |event| {
+            for change in event.changes() {
+                if let Change::Remove(data) = change {
+                    function(&data.value);
+                }
+            }
+        })
+    }
+
+    /// Registers a listener called once for every item moved.
+    ///
+    /// Wraps [`GridEngine::add_changes_listener`], skipping the other kinds of
+    /// changes in each batch, resizes and reorders included.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The callback function, receiving the item before and
+    ///   after the move
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The listener id, used to remove it later
+    /// * `Err(GridEngineError::GridEvent)` - If the listener couldn't be registered
+    pub fn on_move(
+        &mut self,
+        function: impl Fn(&Node, &Node) + Send + 'static + Sync,
+    ) -> Result<String, GridEngineError> {
+        self.add_changes_listener(move |event| {
+            for change in event.changes() {
+                if let Change::Move(data) = change {
+                    function(&data.old_value, &data.new_value);
+                }
+            }
+        })
+    }
+
+    /// Returns a reference to the grid events system.
+    pub fn events(&self) -> &GridEvents {
+        &self.events
+    }
+
+    /// Returns a mutable reference to the grid events system.
+    pub fn events_mut(&mut self) -> &mut GridEvents {
+        &mut self.events
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_for_cell() {
+        let mut results = Vec::new();
+        let mut callback = |x: usize, y: usize| {
+            results.push((x, y));
+            Ok(())
+        };
+
+        for_cell(Position::new(1, 2), Size::new(2, 2), &mut callback).unwrap();
+
+        assert_eq!(results, vec![(1, 2), (1, 3), (2, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn test_add_item() {
+        let mut engine = GridEngine::new(10, 10);
+        let item_0_id = engine
+            .add_item("0".to_string(), 0, 0, 2, 2)
+            .unwrap()
+            .id
+            .clone();
+
+        assert!(engine.items.len() == 1);
+        for_cell(Position::new(0, 0), Size::new(2, 2), &mut |x, y| {
+            assert_eq!(engine.grid.get(x, y).unwrap().unwrap(), item_0_id);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_add_item_handle_duplicated_id() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+
+        assert!(engine.add_item("0".to_string(), 0, 0, 2, 2).is_err())
+    }
+
+    #[test]
+    fn test_add_item_handle_collision() {
+        let mut engine = GridEngine::new(10, 10);
+        let item_0_id = engine
+            .add_item("0".to_string(), 0, 0, 2, 2)
+            .unwrap()
+            .id
+            .clone();
+        let item_1_id = engine
+            .add_item("1".to_string(), 0, 0, 2, 2)
+            .unwrap()
+            .id
+            .clone();
+
+        // Item 0 should stay in position 0, 0
+        let item_0 = engine.items.get(&item_0_id).unwrap();
+        assert_eq!(item_0.x, 0);
+        assert_eq!(item_0.y, 2);
+        item_0
+            .for_cell(&mut |x, y| {
+                assert_eq!(engine.grid.get(x, y).unwrap().unwrap(), item_0_id);
+                Ok(())
+            })
+            .unwrap();
+
+        // Item 1 should go to position 0, 2
+        let item_1 = engine.items.get(&item_1_id).unwrap();
+        assert_eq!(item_1.x, 0);
+        assert_eq!(item_1.y, 0);
+        item_1
+            .for_cell(&mut |x, y| {
+                assert_eq!(engine.grid.get(x, y).unwrap().unwrap(), item_1_id);
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_remove_item() {
+        let mut engine = GridEngine::new(10, 10);
+        let item_0_id = engine
+            .add_item("0".to_string(), 0, 0, 2, 3)
+            .unwrap()
+            .id
+            .clone();
+        engine.remove_item(&item_0_id).unwrap();
+        for_cell(Position::new(0, 0), Size::new(2, 3), &mut |x, y| {
+            let value = engine.grid.get(x, y).unwrap();
+            assert_eq!(value, None);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_move_item() {
+        let mut engine = GridEngine::new(10, 10);
+        let item_0_id = engine
+            .add_item("0".to_string(), 0, 0, 2, 2)
+            .unwrap()
+            .id
+            .clone();
+        engine.move_item(&item_0_id, 1, 1).unwrap();
+
+        // Asserts that its present on the new position
+        for_cell(Position::new(1, 1), Size::new(2, 2), &mut |x, y| {
+            let item_on_expected_position = engine.grid.get(x, y).unwrap().unwrap();
+            assert_eq!(item_on_expected_position, item_0_id);
+            Ok(())
+        })
+        .unwrap();
+
+        // Asserts that its not present on the old position
+        for_cell(Position::new(0, 0), Size::new(1, 1), &mut |x, y| {
+            assert_eq!(engine.grid.get(x, y).unwrap(), None);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_move_item_handle_collision() {
+        let mut engine = GridEngine::new(10, 10);
+        let item_0_id = engine
+            .add_item("0".to_string(), 0, 0, 2, 2)
+            .unwrap()
+            .id
+            .clone();
+        let item_1_id = engine
+            .add_item("1".to_string(), 0, 2, 2, 2)
+            .unwrap()
+            .id
+            .clone();
+        engine.move_item("0", 0, 1).unwrap();
+
+        // Item 0 should go to position 0, 1
+        let item_0 = engine.items.get(&item_0_id).unwrap();
+        assert_eq!(item_0.x, 0);
+        assert_eq!(item_0.y, 1);
+        item_0
+            .for_cell(&mut |x, y| {
+                assert_eq!(engine.grid.get(x, y).unwrap().unwrap(), item_0_id);
+                Ok(())
+            })
+            .unwrap();
+
+        // Item 1 should go to position 0, 3
+        let item_1 = engine.items.get(&item_1_id).unwrap();
+        assert_eq!(item_1.x, 0);
+        assert_eq!(item_1.y, 3);
+        item_1
+            .for_cell(&mut |x, y| {
+                assert_eq!(engine.grid.get(x, y).unwrap().unwrap(), item_1_id);
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_will_collides_with() {
+        let mut engine = GridEngine::new(10, 10);
+        let item_0_id = engine
+            .add_item("0".to_string(), 0, 0, 1, 2)
+            .unwrap()
+            .id
+            .clone();
+
+        // Asserts that does not collide with self
+        assert!(
+            engine
+                .will_collides_with(engine.items.get(&item_0_id).unwrap(), 0, 0, &engine.grid)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Asserts that does not collide with empty position
+        assert!(
+            engine
+                .will_collides_with(engine.items.get(&item_0_id).unwrap(), 2, 2, &engine.grid)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Asserts that collide with occupied position
+        engine.add_item("1".to_string(), 1, 2, 1, 2).unwrap();
+
+        // Full collision
+        assert!(
+            engine
+                .will_collides_with(engine.items.get(&item_0_id).unwrap(), 1, 2, &engine.grid)
+                .unwrap()
+                .len()
+                == 1
+        );
+
+        // Partial collision
+        assert!(
+            engine
+                .will_collides_with(engine.items.get(&item_0_id).unwrap(), 1, 1, &engine.grid)
+                .unwrap()
+                .len()
+                == 1
+        );
+    }
+
+    #[test]
+    fn test_get_nodes() {
+        let mut engine = GridEngine::new(10, 10);
+        let item_0_id = engine
+            .add_item("0".to_string(), 0, 0, 2, 2)
+            .unwrap()
+            .id
+            .clone();
+        let item_1_id = engine
+            .add_item("1".to_string(), 0, 2, 2, 2)
+            .unwrap()
+            .id
+            .clone();
+
+        let nodes = engine.get_nodes();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].id, item_0_id);
+        assert_eq!(nodes[1].id, item_1_id);
+    }
+
+    #[test]
+    fn test_move_result_will_not_collides_with_moving_item() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 3).unwrap();
+        engine.add_item("1".to_string(), 0, 6, 2, 2).unwrap();
+        engine.move_item("1", 0, 2).unwrap();
+
+        for_cell(Position::new(0, 7), Size::new(2, 2), &mut |x, y| {
+            let value = engine.grid.get(x, y).unwrap();
+            println!("value: {:?}", value);
+            assert_ne!(value, Some("1"));
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_node_movements_that_collides_twice_works() {
+        let mut engine = GridEngine::new(14, 10);
+        engine.add_item("0".to_string(), 1, 1, 2, 3).unwrap();
+        engine.add_item("1".to_string(), 2, 4, 2, 4).unwrap();
+        engine.add_item("2".to_string(), 0, 6, 2, 4).unwrap();
+        engine.move_item("2", 1, 2).unwrap();
+
+        println!("Items: {:#?}", engine.items);
+
+        engine.items.iter().for_each(|(_, node)| {
+            node.for_cell(&mut |x, y| {
+                let value = engine.grid.get(x, y).unwrap();
+                println!("Validating x: {}, y: {}", x, y);
+                assert_eq!(Some(node.id.as_str()), value);
+                Ok(())
+            })
+            .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_dominant_item_in() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 2, 0, 3, 2).unwrap();
+
+        // Region covers 2 cells of item 0 and 6 cells of item 1
+        let dominant = engine.dominant_item_in(1, 0, 4, 2).unwrap();
+        assert_eq!(dominant.id, "1");
+
+        // Ties are resolved by the smallest id
+        let dominant = engine.dominant_item_in(1, 0, 2, 2).unwrap();
+        assert_eq!(dominant.id, "0");
+
+        // Empty region
+        assert!(engine.dominant_item_in(6, 6, 2, 2).is_none());
+    }
+
+    #[test]
+    fn test_move_item_swap_strategy() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        engine.set_collision_strategy(CollisionStrategy::Swap);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 4, 0, 2, 2).unwrap();
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |event| {
+                received_clone
+                    .lock()
+                    .unwrap()
+                    .extend(event.changes().clone());
+            })
+            .unwrap();
+
+        engine.move_item("0", 4, 0).unwrap();
+
+        let changes = received.lock().unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(
+            changes
+                .iter()
+                .all(|change| matches!(change, Change::Move(_)))
+        );
+
+        let item_0 = engine.items.get("0").unwrap();
+        assert_eq!((item_0.x, item_0.y), (4, 0));
+        let item_1 = engine.items.get("1").unwrap();
+        assert_eq!((item_1.x, item_1.y), (0, 0));
+
+        engine.items.iter().for_each(|(_, node)| {
+            node.for_cell(&mut |x, y| {
+                assert_eq!(engine.grid.get(x, y).unwrap(), Some(node.id.as_str()));
+                Ok(())
+            })
+            .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_move_item_swap_strategy_falls_back_to_push() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.set_collision_strategy(CollisionStrategy::Swap);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 4, 0, 2, 3).unwrap();
+
+        engine.move_item("0", 4, 0).unwrap();
+
+        let item_1 = engine.items.get("1").unwrap();
+        assert_eq!((item_1.x, item_1.y), (4, 2));
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 2, 0, 2, 2).unwrap();
+
+        let expected = engine
+            .get_nodes()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<Node>>();
+        let expected_grid = engine.grid.clone();
+        let snapshot = engine.snapshot();
+
+        engine.move_item("0", 2, 0).unwrap();
+        engine.remove_item("1").unwrap();
+        engine.add_item("2".to_string(), 5, 5, 1, 1).unwrap();
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |event| {
+                received_clone.lock().unwrap().push(event.changes().clone());
+            })
+            .unwrap();
+
+        engine.restore(snapshot).unwrap();
+
+        let nodes = engine
+            .get_nodes()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<Node>>();
+        assert_eq!(nodes, expected);
+        assert_eq!(engine.grid, expected_grid);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].len(), 3);
+    }
+
+    #[test]
+    fn test_trim_to_content_respects_min_rows() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.set_min_rows(5);
+
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 0, 12, 2, 2).unwrap();
+        assert_eq!(engine.grid.rows(), 14);
+
+        engine.trim_to_content().unwrap();
+        assert_eq!(engine.grid.rows(), 14);
+
+        engine.remove_item("0").unwrap();
+        engine.remove_item("1").unwrap();
+        engine.trim_to_content().unwrap();
+        assert_eq!(engine.grid.rows(), 5);
+    }
+
+    #[test]
+    fn test_move_item_to_same_position_is_noop() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 1, 1, 2, 2).unwrap();
+
+        let counter = Arc::new(Mutex::new(0));
+        let counter_clone = counter.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |_| {
+                *counter_clone.lock().unwrap() += 1;
+            })
+            .unwrap();
+
+        engine.move_item("0", 1, 1).unwrap();
+
+        assert_eq!(*counter.lock().unwrap(), 0);
+        let item_0 = engine.items.get("0").unwrap();
+        assert_eq!((item_0.x, item_0.y), (1, 1));
+    }
+
+    #[test]
+    fn test_save_and_restore_item_state() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 4, 0, 2, 2).unwrap();
+
+        let saved = engine.save_item_state("0").unwrap();
+        engine.move_item("0", 6, 6).unwrap();
+        engine.restore_item_state(saved.clone()).unwrap();
+
+        let item_0 = engine.items.get("0").unwrap();
+        assert_eq!(item_0, &saved);
+        item_0
+            .for_cell(&mut |x, y| {
+                assert_eq!(engine.grid.get(x, y).unwrap(), Some("0"));
+                Ok(())
+            })
+            .unwrap();
+
+        // Restoring a different size resolves collisions with the new footprint
+        engine
+            .restore_item_state(Node::new("0", 0, 0, 5, 2))
+            .unwrap();
+        let item_1 = engine.items.get("1").unwrap();
+        assert_eq!((item_1.x, item_1.y), (4, 2));
+        assert!(engine.save_item_state("missing").is_none());
+    }
+
+    #[test]
+    fn test_try_add_item_strict() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.try_add_item_strict("0", 0, 0, 2, 2).unwrap();
+
+        let result = engine.try_add_item_strict("1", 1, 1, 2, 2);
+        match result {
+            Err(GridEngineError::Item(ItemError::PositionOccupied { ids })) => {
+                assert_eq!(ids, vec!["0".to_string()]);
+            }
+            other => panic!("Expected PositionOccupied, got {:?}", other),
+        }
+
+        // Nothing was moved or added
+        assert_eq!(engine.items.len(), 1);
+        let item_0 = engine.items.get("0").unwrap();
+        assert_eq!((item_0.x, item_0.y), (0, 0));
+    }
+
+    #[test]
+    fn test_empty_columns_and_rows() {
+        let mut engine = GridEngine::new(6, 6);
+        engine.add_item("0".to_string(), 1, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 3, 3, 2, 1).unwrap();
+
+        assert_eq!(engine.empty_columns(), vec![0, 5]);
+        assert_eq!(engine.empty_rows(), vec![2, 4, 5]);
+    }
+
+    #[test]
+    fn test_pre_change_listener_vetoes_move() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 2, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 4, 2, 2, 2).unwrap();
+        let grid_before = engine.grid.clone();
+
+        engine
+            .events_mut()
+            .add_pre_change_listener(|event| {
+                event.changes().iter().all(|change| match change {
+                    Change::Move(data) => data.new_value().y > 0,
+                    _ => true,
+                })
+            })
+            .unwrap();
+
+        let result = engine.move_item("0", 4, 0);
+        assert!(matches!(result, Err(GridEngineError::ChangesVetoed)));
+
+        let item_0 = engine.items.get("0").unwrap();
+        assert_eq!((item_0.x, item_0.y), (0, 2));
+        let item_1 = engine.items.get("1").unwrap();
+        assert_eq!((item_1.x, item_1.y), (4, 2));
+        assert_eq!(engine.grid, grid_before);
+        assert!(engine.pending_changes.is_empty());
+
+        // Allowed changes still go through
+        engine.move_item("0", 0, 5).unwrap();
+        assert_eq!(engine.items.get("0").unwrap().y, 5);
+    }
+
+    #[test]
+    fn test_canonical_json_is_order_independent() {
+        let mut engine_a = GridEngine::new(4, 6);
+        engine_a.add_item("a".to_string(), 0, 0, 2, 2).unwrap();
+        engine_a.add_item("b".to_string(), 2, 0, 2, 2).unwrap();
+
+        let mut engine_b = GridEngine::new(4, 6);
+        engine_b.add_item("b".to_string(), 0, 6, 2, 2).unwrap();
+        engine_b.add_item("a".to_string(), 4, 4, 2, 2).unwrap();
+        engine_b.move_item("b", 2, 0).unwrap();
+        engine_b.move_item("a", 0, 0).unwrap();
+
+        assert_eq!(
+            engine_a.snapshot().to_canonical_json(),
+            engine_b.snapshot().to_canonical_json()
+        );
+        assert_eq!(
+            engine_a.snapshot().to_canonical_json(),
+            r#"{"cols":6,"items":[{"id":"a","x":0,"y":0,"w":2,"h":2},{"id":"b","x":2,"y":0,"w":2,"h":2}]}"#
+        );
+    }
+
+    #[test]
+    fn test_json_string_escapes() {
+        assert_eq!(json_string("a\"b\\c\n"), r#""a\"b\\c\n""#);
+    }
+
+    #[test]
+    fn test_occupancy() {
+        let mut engine = GridEngine::new(10, 10);
+
+        let stats = engine.occupancy();
+        assert_eq!(stats.occupied_cells, 0);
+        assert_eq!(stats.free_cells, 100);
+        assert_eq!(stats.bounding_box, None);
+
+        engine.add_item("0".to_string(), 1, 2, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 6, 5, 2, 2).unwrap();
+
+        let stats = engine.occupancy();
+        assert_eq!(stats.total_cells, 100);
+        assert_eq!(stats.occupied_cells, 8);
+        assert_eq!(stats.free_cells, 92);
+        assert_eq!(stats.fill_ratio, 0.08);
+        assert_eq!(stats.bounding_box, Some((1, 2, 7, 5)));
+    }
+
+    #[test]
+    fn test_collision_respects_weight() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("light".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("heavy".to_string(), 4, 0, 2, 2).unwrap();
+        engine.set_item_weight("heavy", 5).unwrap();
+
+        // Heavy item moved onto the light one pushes it down
+        engine.move_item("heavy", 0, 0).unwrap();
+        let heavy = engine.items.get("heavy").unwrap();
+        assert_eq!((heavy.x, heavy.y, heavy.weight), (0, 0, 5));
+        let light = engine.items.get("light").unwrap();
+        assert_eq!((light.x, light.y), (0, 2));
+
+        // Light item added onto the heavy one is relocated itself
+        engine.add_item("new".to_string(), 0, 0, 2, 1).unwrap();
+        let heavy = engine.items.get("heavy").unwrap();
+        assert_eq!((heavy.x, heavy.y), (0, 0));
+        let new = engine.items.get("new").unwrap();
+        assert_eq!((new.x, new.y), (0, 2));
+        // And pushes the equally light item below it
+        let light = engine.items.get("light").unwrap();
+        assert_eq!((light.x, light.y), (0, 3));
+
+        engine.items.iter().for_each(|(_, node)| {
+            node.for_cell(&mut |x, y| {
+                assert_eq!(engine.grid.get(x, y).unwrap(), Some(node.id.as_str()));
+                Ok(())
+            })
+            .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_clone_resets_listeners() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 2, 0, 2, 2).unwrap();
+
+        let counter = Arc::new(Mutex::new(0));
+        let counter_clone = counter.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |_| {
+                *counter_clone.lock().unwrap() += 1;
+            })
+            .unwrap();
+
+        let mut cloned = engine.clone();
+        assert_eq!(cloned.get_nodes(), engine.get_nodes());
+        assert_eq!(cloned.grid, engine.grid);
+
+        // Changes on the clone are not reported to the original listeners
+        cloned.move_item("0", 5, 5).unwrap();
+        assert_eq!(*counter.lock().unwrap(), 0);
+        assert_eq!(engine.items.get("0").unwrap().x, 0);
+
+        engine.move_item("0", 5, 5).unwrap();
+        assert_eq!(*counter.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_move_item_by() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+
+        assert_eq!(engine.move_item_by("0", -1, -1).unwrap(), (0, 0));
+        let item_0 = engine.items.get("0").unwrap();
+        assert_eq!((item_0.x, item_0.y), (0, 0));
+
+        assert_eq!(engine.move_item_by("0", 2, 3).unwrap(), (2, 3));
+        let item_0 = engine.items.get("0").unwrap();
+        assert_eq!((item_0.x, item_0.y), (2, 3));
+
+        // Clamped at the right edge
+        assert_eq!(engine.move_item_by("0", 100, 0).unwrap(), (8, 3));
+
+        assert!(engine.move_item_by("missing", 1, 1).is_err());
+    }
+
+    #[test]
+    fn test_to_dense() {
+        let mut engine = GridEngine::new(4, 6);
+        engine.add_item("0".to_string(), 2, 1, 2, 3).unwrap();
+
+        let dense = engine.to_dense();
+        assert_eq!(dense.len(), 4);
+        assert!(dense.iter().all(|row| row.len() == 6));
+
+        for (y, row) in dense.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let inside = (2..4).contains(&x) && (1..4).contains(&y);
+                let expected = inside.then(|| "0".to_string());
+                assert_eq!(cell, &expected, "Unexpected cell at x: {}, y: {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rename_item() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 1, 1, 2, 3).unwrap();
+        engine.add_item("1".to_string(), 4, 0, 2, 2).unwrap();
+
+        engine.rename_item("0", "renamed").unwrap();
+
+        assert!(!engine.items.contains_key("0"));
+        let renamed = engine.items.get("renamed").unwrap();
+        assert_eq!((renamed.x, renamed.y, renamed.w, renamed.h), (1, 1, 2, 3));
+        renamed
+            .for_cell(&mut |x, y| {
+                assert_eq!(engine.grid.get(x, y).unwrap(), Some("renamed"));
+                Ok(())
+            })
+            .unwrap();
+        assert!(
+            engine
+                .grid
+                .iter_cells()
+                .all(|(_, _, cell)| cell != Some("0"))
+        );
+
+        // Renaming onto an existing id fails without changes
+        let grid_before = engine.grid.clone();
+        assert!(matches!(
+            engine.rename_item("renamed", "1"),
+            Err(GridEngineError::Item(ItemError::ItemAlreadyExists { .. }))
+        ));
+        assert!(matches!(
+            engine.rename_item("missing", "2"),
+            Err(GridEngineError::Item(ItemError::ItemNotFound { .. }))
+        ));
+        assert_eq!(engine.grid, grid_before);
+        assert_eq!(engine.items.len(), 2);
+    }
+
+    #[test]
+    fn test_flow_right_strategy() {
+        let mut engine = GridEngine::new(4, 6);
+        engine.set_collision_strategy(CollisionStrategy::FlowRight);
+        engine.add_item("0".to_string(), 0, 0, 2, 1).unwrap();
+
+        // Fits on the right
+        engine.add_item("1".to_string(), 0, 0, 2, 1).unwrap();
+        let item_0 = engine.items.get("0").unwrap();
+        assert_eq!((item_0.x, item_0.y), (2, 0));
+
+        // Pushed off the right edge wraps to the next row
+        let mut engine = GridEngine::new(4, 4);
+        engine.set_collision_strategy(CollisionStrategy::FlowRight);
+        engine.add_item("0".to_string(), 2, 0, 2, 1).unwrap();
+        engine.add_item("1".to_string(), 2, 0, 2, 1).unwrap();
+
+        let item_0 = engine.items.get("0").unwrap();
+        assert_eq!((item_0.x, item_0.y), (0, 1));
+        let item_1 = engine.items.get("1").unwrap();
+        assert_eq!((item_1.x, item_1.y), (2, 0));
+    }
+
+    #[test]
+    fn test_nodes_in_row_and_col() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 3, 1, 3, 3).unwrap();
+        engine.add_item("2".to_string(), 7, 4, 2, 2).unwrap();
+        engine.add_item("3".to_string(), 0, 5, 2, 2).unwrap();
+
+        let row = engine.nodes_in_row(1);
+        assert_eq!(
+            row.iter().map(|n| n.id.as_str()).collect::<Vec<&str>>(),
+            vec!["0", "1"]
+        );
+
+        let col = engine.nodes_in_col(1);
+        assert_eq!(
+            col.iter().map(|n| n.id.as_str()).collect::<Vec<&str>>(),
+            vec!["0", "3"]
+        );
+
+        // Outside of the grid
+        assert!(engine.nodes_in_row(50).is_empty());
+        assert!(engine.nodes_in_col(50).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 4, 0, 2, 2).unwrap();
+        let before = engine.snapshot();
+
+        engine.add_item("2".to_string(), 0, 6, 1, 1).unwrap();
+        engine.move_item("1", 4, 3).unwrap();
+        let after = engine.snapshot();
+
+        let changes = before.diff(&after);
+        assert_eq!(changes.len(), 2);
+        assert!(changes.contains(&Change::Move(MoveChangeData::new(
+            Node {
+                seq: 1,
+                ..Node::new("1", 4, 0, 2, 2)
+            },
+            Node {
+                seq: 1,
+                ..Node::new("1", 4, 3, 2, 2)
+            }
+        ))));
+        assert!(changes.contains(&Change::Add(AddChangeData::new(Node {
+            seq: 2,
+            ..Node::new("2", 0, 6, 1, 1)
+        }))));
+
+        assert!(after.diff(&after).is_empty());
+    }
+
+    #[test]
+    fn test_apply_external() {
+        use std::sync::{Arc, Mutex};
+
+        let mut peer = GridEngine::new(10, 10);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        peer.events_mut()
+            .add_changes_listener(move |event| {
+                received_clone.lock().unwrap().push(event.changes().clone());
+            })
+            .unwrap();
+
+        peer.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        peer.add_item("1".to_string(), 0, 2, 2, 2).unwrap();
+        peer.move_item("0", 0, 1).unwrap();
+
+        let mut local = GridEngine::new(10, 10);
+        for changes in received.lock().unwrap().iter() {
+            local.apply_external(changes.clone()).unwrap();
+        }
+
+        assert_eq!(local.get_nodes(), peer.get_nodes());
+        assert_eq!(local.to_dense(), peer.to_dense());
+    }
+
+    #[test]
+    fn test_apply_external_rejects_invalid_changes() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        let grid_before = engine.grid.clone();
+
+        // Valid change followed by a duplicated add, nothing is applied
+        let result = engine.apply_external(vec![
+            Change::Add(AddChangeData::new(Node::new("1", 4, 4, 1, 1))),
+            Change::Add(AddChangeData::new(Node::new("0", 6, 6, 1, 1))),
+        ]);
+        assert!(matches!(
+            result,
+            Err(GridEngineError::Item(ItemError::ItemAlreadyExists { .. }))
+        ));
+
+        let result = engine.apply_external(vec![Change::Remove(RemoveChangeData::new(Node::new(
+            "missing", 0, 0, 1, 1,
+        )))]);
+        assert!(matches!(
+            result,
+            Err(GridEngineError::Item(ItemError::ItemNotFound { .. }))
+        ));
+
+        let result = engine.apply_external(vec![Change::Move(MoveChangeData::new(
+            Node::new("0", 3, 3, 2, 2),
+            Node::new("0", 5, 5, 2, 2),
+        ))]);
+        assert!(matches!(
+            result,
+            Err(GridEngineError::InnerGrid(
+                InnerGridError::MismatchedGridItem { .. }
+            ))
+        ));
+
+        assert_eq!(engine.items.len(), 1);
+        assert_eq!(engine.grid, grid_before);
+    }
+
+    #[test]
+    fn test_get_grid_formatted_with_options() {
+        let mut engine = GridEngine::new(2, 3);
+        engine.add_item("a".to_string(), 1, 0, 1, 2).unwrap();
+
+        let formatted = engine.get_grid_formatted_with(GridFormatOptions {
+            open: '(',
+            close: ')',
+            empty: '.',
+            cell_width: 2,
+        });
+        assert_eq!(formatted, "(..)(a.)(..)\n(..)(a.)(..)\n");
+
+        assert_eq!(engine.get_grid_formatted(1), "[ ][a][ ]\n[ ][a][ ]\n");
+    }
+
+    #[test]
+    fn test_is_region_free() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 2, 2, 2, 2).unwrap();
+
+        assert!(engine.is_region_free(0, 0, 2, 2));
+        assert!(engine.is_region_free(4, 2, 6, 8));
+        assert!(!engine.is_region_free(3, 3, 2, 2));
+        assert!(!engine.is_region_free(0, 0, 3, 3));
+
+        // Out of bounds regions are not free and don't expand the grid
+        assert!(!engine.is_region_free(9, 0, 2, 1));
+        assert!(!engine.is_region_free(0, 9, 1, 2));
+        assert_eq!(engine.grid.rows(), 10);
+    }
+
+    #[test]
+    fn test_single_event_per_mutating_call() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |event| {
+                events_clone.lock().unwrap().push(event.changes().clone());
+            })
+            .unwrap();
+
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        assert_eq!(events.lock().unwrap().len(), 1);
+
+        // Add with a collision still fires a single event with the whole batch
+        engine.add_item("1".to_string(), 0, 0, 2, 2).unwrap();
+        assert_eq!(events.lock().unwrap().len(), 2);
+        assert_eq!(events.lock().unwrap()[1].len(), 2);
+
+        engine.add_item("2".to_string(), 2, 0, 2, 2).unwrap();
+        events.lock().unwrap().clear();
+
+        // Move displacing two items
+        engine.move_item("2", 0, 1).unwrap();
+        {
+            let events = events.lock().unwrap();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].len(), 3);
+        }
+
+        engine.remove_item("1").unwrap();
+        assert_eq!(events.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_expansion_toggles() {
+        let mut engine = GridEngine::new(4, 4);
+        assert!(engine.can_expand_y());
+        assert!(!engine.can_expand_x());
+
+        engine.set_expand_y(false);
+        assert!(matches!(
+            engine.add_item("0".to_string(), 0, 3, 2, 2),
+            Err(GridEngineError::InnerGrid(
+                InnerGridError::OutOfBoundsAccess { .. }
+            ))
+        ));
+        assert_eq!(engine.grid.rows(), 4);
+        assert!(engine.items.is_empty());
+
+        engine.set_expand_y(true);
+        engine.add_item("0".to_string(), 0, 3, 2, 2).unwrap();
+        assert_eq!(engine.grid.rows(), 5);
+
+        assert!(engine.add_item("1".to_string(), 3, 0, 2, 1).is_err());
+        engine.set_expand_x(true);
+        engine.add_item("1".to_string(), 3, 0, 2, 1).unwrap();
+        assert_eq!(engine.grid.cols(), 5);
+    }
+
+    #[test]
+    fn test_restore_applies_config() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.set_expand_y(false);
+        engine.set_collision_strategy(CollisionStrategy::Swap);
+        let snapshot = engine.snapshot();
+        assert_eq!(snapshot.schema_version(), SNAPSHOT_SCHEMA_VERSION);
+
+        let mut restored = GridEngine::new(4, 4);
+        restored.restore(snapshot).unwrap();
+        assert!(!restored.can_expand_y());
+        assert_eq!(restored.collision_strategy(), CollisionStrategy::Swap);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_serde_round_trip() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.set_expand_y(false);
+
+        let json = serde_json::to_string(&engine.snapshot()).unwrap();
+        let snapshot: GridSnapshot = serde_json::from_str(&json).unwrap();
+        assert!(!snapshot.can_expand_y());
+        assert_eq!(snapshot, engine.snapshot());
+
+        let mut restored = GridEngine::new(1, 4);
+        restored.restore(snapshot).unwrap();
+        assert!(!restored.can_expand_y());
+        assert!(restored.add_item("1".to_string(), 0, 3, 1, 2).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_deserializes_unversioned_json() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+
+        let mut value = serde_json::to_value(engine.snapshot()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("schema_version");
+        object.remove("collision_strategy");
+        let grid = object.get_mut("grid").unwrap().as_object_mut().unwrap();
+        grid.remove("can_expand_y");
+        grid.remove("can_expand_x");
+        for node in object["items"].as_object_mut().unwrap().values_mut() {
+            node.as_object_mut().unwrap().remove("weight");
+        }
+
+        let snapshot: GridSnapshot = serde_json::from_value(value).unwrap();
+        assert_eq!(snapshot.schema_version(), 0);
+        assert!(snapshot.can_expand_y());
+        assert!(!snapshot.can_expand_x());
+        assert_eq!(snapshot.collision_strategy(), CollisionStrategy::PushDown);
+        assert_eq!(snapshot.items, engine.snapshot().items);
+    }
+
+    #[test]
+    fn test_remove_items() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 2, 0, 2, 2).unwrap();
+        engine.add_item("2".to_string(), 4, 0, 2, 2).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |event| {
+                events_clone.lock().unwrap().push(event.changes().len());
+            })
+            .unwrap();
+
+        let removed = engine.remove_items(&["0", "2"]).unwrap();
+        assert_eq!(
+            removed.iter().map(|node| node.id()).collect::<Vec<_>>(),
+            vec!["0", "2"]
+        );
+        assert_eq!(*events.lock().unwrap(), vec![2]);
+        assert_eq!(engine.items.len(), 1);
+        assert!(engine.items.contains_key("1"));
+        assert_eq!(engine.grid.get(0, 0), Some(None));
+        assert_eq!(engine.grid.get(2, 0), Some(Some("1")));
+    }
+
+    #[test]
+    fn test_remove_items_unknown_id_removes_nothing() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 2, 0, 2, 2).unwrap();
+
+        let result = engine.remove_items(&["0", "missing"]);
+        assert!(matches!(
+            result,
+            Err(GridEngineError::Item(ItemError::ItemNotFound { id })) if id == "missing"
+        ));
+        assert_eq!(engine.items.len(), 2);
+        assert_eq!(engine.grid.get(0, 0), Some(Some("0")));
+    }
+
+    #[test]
+    fn test_add_item_too_wide() {
+        let mut engine = GridEngine::new(10, 10);
+
+        let result = engine.add_item("0".to_string(), 0, 0, 11, 1);
+        assert!(matches!(
+            result,
+            Err(GridEngineError::Item(ItemError::TooWide {
+                w: 11,
+                cols: 10
+            }))
+        ));
+        assert!(matches!(
+            engine.try_add_item_strict("0".to_string(), 0, 0, 11, 1),
+            Err(GridEngineError::Item(ItemError::TooWide {
+                w: 11,
+                cols: 10
+            }))
+        ));
+        assert!(engine.items.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one column")]
+    fn test_new_rejects_zero_cols() {
+        GridEngine::new(10, 0);
+    }
+
+    #[test]
+    fn test_try_new_with_options_rejects_zero_cols() {
+        assert!(matches!(
+            GridEngine::try_new_with_options(GridOptions::new(10, 0)),
+            Err(GridEngineError::NoColumns { rows: 10, cols: 0 })
+        ));
+        assert!(matches!(
+            GridEngine::from_dense(vec![vec![], vec![]]),
+            Err(GridEngineError::NoColumns { rows: 2, cols: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_capacity_for() {
+        let mut engine = GridEngine::new(4, 4);
+        assert_eq!(engine.capacity_for(2, 2), 4);
+        assert_eq!(engine.capacity_for(1, 1), 16);
+        assert_eq!(engine.capacity_for(5, 1), 0);
+        assert_eq!(engine.capacity_for(0, 1), 0);
+
+        engine.add_item("0".to_string(), 1, 1, 2, 2).unwrap();
+        assert_eq!(engine.capacity_for(2, 2), 0);
+        assert_eq!(engine.capacity_for(1, 1), 12);
+        assert_eq!(engine.grid.rows(), 4);
+    }
+
+    #[test]
+    fn test_get_nodes_by_creation() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("c".to_string(), 0, 0, 1, 1).unwrap();
+        engine.add_item("a".to_string(), 1, 0, 1, 1).unwrap();
+        engine.add_item("b".to_string(), 2, 0, 1, 1).unwrap();
+
+        // Moving keeps the creation order
+        engine.move_item("c", 5, 5).unwrap();
+
+        let ids: Vec<&str> = engine
+            .get_nodes_by_creation()
+            .iter()
+            .map(|node| node.id())
+            .collect();
+        assert_eq!(ids, vec!["c", "a", "b"]);
+
+        let ids: Vec<&str> = engine.get_nodes().iter().map(|node| node.id()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_auto_strategy_prefers_smaller_displacement() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.set_collision_strategy(CollisionStrategy::Auto);
+
+        // Overlapping by one column but four rows, pushing right is cheaper
+        engine.add_item("0".to_string(), 4, 0, 2, 4).unwrap();
+        engine.add_item("1".to_string(), 3, 0, 2, 4).unwrap();
+        assert_eq!(engine.items["0"].x, 5);
+        assert_eq!(engine.items["0"].y, 0);
+
+        // Overlapping by one row but two columns, pushing down is cheaper
+        engine.add_item("2".to_string(), 0, 5, 2, 1).unwrap();
+        engine.add_item("3".to_string(), 0, 4, 2, 2).unwrap();
+        assert_eq!(engine.items["2"].x, 0);
+        assert_eq!(engine.items["2"].y, 6);
+    }
+
+    #[test]
+    fn test_snapshot_equality() {
+        let build = || {
+            let mut engine = GridEngine::new(10, 10);
+            engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+            engine.add_item("1".to_string(), 0, 0, 2, 2).unwrap();
+            engine.move_item("1", 4, 4).unwrap();
+            engine
+        };
+
+        let mut first = build();
+        let second = build();
+        assert_eq!(first.snapshot(), second.snapshot());
+
+        first.move_item("1", 5, 4).unwrap();
+        assert_ne!(first.snapshot(), second.snapshot());
+    }
+
+    #[test]
+    fn test_reserve_rows() {
+        let mut engine = GridEngine::new(4, 10);
+        engine.reserve_rows(200).unwrap();
+        assert_eq!(engine.grid.rows(), 200);
+        assert_eq!(engine.grid.cols(), 10);
+
+        // Adds within the reserved rows don't grow the grid
+        for i in 0..50 {
+            engine.add_item(i.to_string(), 0, 150 + i, 1, 1).unwrap();
+        }
+        assert_eq!(engine.grid.rows(), 200);
+
+        // Never shrinks
+        engine.reserve_rows(10).unwrap();
+        assert_eq!(engine.grid.rows(), 200);
+    }
+
+    #[test]
+    fn test_new_with_options() {
+        let mut engine = GridEngine::new_with_options(GridOptions {
+            can_expand_y: false,
+            collision_strategy: CollisionStrategy::FlowRight,
+            ..GridOptions::new(4, 10)
+        });
+        assert_eq!(engine.collision_strategy(), CollisionStrategy::FlowRight);
+
+        assert!(engine.add_item("0".to_string(), 0, 3, 1, 2).is_err());
+
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 0, 0, 2, 2).unwrap();
+        assert_eq!((engine.items["0"].x, engine.items["0"].y), (2, 0));
+        assert_eq!(engine.grid.rows(), 4);
+    }
+
+    #[test]
+    fn test_max_rows() {
+        let mut engine = GridEngine::new_with_options(GridOptions {
+            max_rows: Some(6),
+            ..GridOptions::new(4, 4)
+        });
+
+        engine.add_item("0".to_string(), 0, 4, 1, 2).unwrap();
+        assert_eq!(engine.grid.rows(), 6);
+        assert!(engine.add_item("1".to_string(), 0, 5, 1, 2).is_err());
+
+        engine.reserve_rows(100).unwrap();
+        assert_eq!(engine.grid.rows(), 6);
+    }
+
+    #[test]
+    fn test_listener_registration_failure_is_typed() {
+        use crate::error::GridEventError;
+
+        let mut engine = GridEngine::new(10, 10);
+        assert!(engine.add_changes_listener(|_| {}).is_ok());
+
+        engine.events().poison_listener_id_counter();
+        assert!(matches!(
+            engine.add_changes_listener(|_| {}),
+            Err(GridEngineError::GridEvent(
+                GridEventError::ListenerIdNotGenerated
+            ))
+        ));
+        assert!(matches!(
+            engine.add_pre_change_listener(|_| true),
+            Err(GridEngineError::GridEvent(
+                GridEventError::ListenerIdNotGenerated
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_grid_event_error_propagates() {
+        use crate::error::GridEventError;
+
+        fn register(engine: &mut GridEngine) -> Result<String, GridEngineError> {
+            let id = engine.events_mut().add_changes_listener(|_| {})?;
+            Ok(id)
+        }
+
+        let mut engine = GridEngine::new(10, 10);
+        assert!(register(&mut engine).is_ok());
+
+        engine.events().poison_listener_id_counter();
+        assert!(matches!(
+            register(&mut engine),
+            Err(GridEngineError::GridEvent(
+                GridEventError::ListenerIdNotGenerated
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_insert_row() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("0".to_string(), 0, 0, 1, 1).unwrap();
+        engine.add_item("1".to_string(), 0, 1, 2, 2).unwrap();
+        engine.add_item("2".to_string(), 0, 3, 2, 1).unwrap();
+
+        engine.insert_row(1).unwrap();
+        assert_eq!(engine.grid.rows(), 5);
+        assert_eq!(engine.items["0"].y, 0);
+        assert_eq!(engine.items["1"].y, 2);
+        assert_eq!(engine.items["2"].y, 4);
+        assert_eq!(engine.grid.get(0, 1), Some(None));
+        assert_eq!(engine.grid.get(0, 2), Some(Some("1")));
+        assert_eq!(engine.grid.get(0, 4), Some(Some("2")));
+
+        // Row 3 is covered by "1", which starts at row 2
+        assert!(matches!(
+            engine.insert_row(3),
+            Err(GridEngineError::Item(ItemError::StraddlesRow { id, row: 3 })) if id == "1"
+        ));
+        assert!(engine.insert_row(6).is_err());
+        assert_eq!(engine.grid.rows(), 5);
+    }
+
+    #[test]
+    fn test_delete_row() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.set_min_rows(1);
+        engine.add_item("0".to_string(), 0, 0, 1, 1).unwrap();
+        engine.add_item("1".to_string(), 1, 1, 1, 1).unwrap();
+        engine.add_item("2".to_string(), 0, 2, 2, 2).unwrap();
+
+        // "2" covers rows 2 and 3
+        assert!(engine.delete_row(3).is_err());
+        assert!(engine.delete_row(2).is_err());
+        assert!(engine.delete_row(4).is_err());
+
+        let removed = engine.delete_row(1).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].id, "1");
+        assert_eq!(engine.grid.rows(), 3);
+        assert_eq!(engine.items["0"].y, 0);
+        assert_eq!(engine.items["2"].y, 1);
+        assert_eq!(engine.grid.get(0, 1), Some(Some("2")));
+        assert_eq!(engine.grid.get(1, 2), Some(Some("2")));
+    }
+
+    #[test]
+    fn test_align_to_grid() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 1, 0, 3, 1).unwrap();
+        engine.add_item("1".to_string(), 3, 1, 2, 1).unwrap();
+        engine.add_item("2".to_string(), 6, 4, 2, 2).unwrap();
+
+        let events = Arc::new(Mutex::new(0));
+        let events_clone = events.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |_| *events_clone.lock().unwrap() += 1)
+            .unwrap();
+
+        engine.align_to_grid(2, 2).unwrap();
+        assert_eq!(*events.lock().unwrap(), 1);
+
+        // "1" snaps onto "0", which is pushed down
+        assert_eq!((engine.items["1"].x, engine.items["1"].y), (2, 0));
+        assert_eq!((engine.items["0"].x, engine.items["0"].y), (0, 1));
+        // Already aligned
+        assert_eq!((engine.items["2"].x, engine.items["2"].y), (6, 4));
+
+        for node in engine.items.values() {
+            let cells = engine
+                .grid
+                .iter_cells()
+                .filter(|(_, _, cell)| *cell == Some(node.id.as_str()))
+                .count();
+            assert_eq!(cells, node.w * node.h);
+        }
+    }
+
+    #[test]
+    fn test_move_item_strict() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 2, 0, 2, 2).unwrap();
+
+        let result = engine.move_item_strict("0", 1, 1);
+        assert!(matches!(
+            result,
+            Err(GridEngineError::Item(ItemError::PositionOccupied { ids })) if ids == vec!["1".to_string()]
+        ));
+        assert_eq!((engine.items["0"].x, engine.items["0"].y), (0, 0));
+        assert_eq!((engine.items["1"].x, engine.items["1"].y), (2, 0));
+        assert_eq!(engine.grid.get(0, 0), Some(Some("0")));
+
+        // Overlapping its own footprint is fine
+        engine.move_item_strict("0", 0, 1).unwrap();
+        assert_eq!((engine.items["0"].x, engine.items["0"].y), (0, 1));
+        assert_eq!(engine.grid.get(0, 0), Some(None));
+        assert_eq!(engine.grid.get(0, 2), Some(Some("0")));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let mut engine = GridEngine::new(10, 10);
+        assert_eq!(engine.bounding_box(), None);
+
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 4, 4, 1, 1).unwrap();
+        assert_eq!(engine.bounding_box(), Some((0, 0, 5, 5)));
+        assert_eq!(engine.bounding_box(), engine.occupancy().bounding_box);
+    }
+
+    #[test]
+    fn test_resize_item() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 0, 2, 2, 1).unwrap();
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |event| {
+                received_clone
+                    .lock()
+                    .unwrap()
+                    .extend(event.changes().clone());
+            })
+            .unwrap();
+
+        engine.resize_item("0", 3, 3).unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(received.contains(&Change::Resize(ResizeChangeData::new(
+            Node::new("0", 0, 0, 2, 2),
+            Node::new("0", 0, 0, 3, 3)
+        ))));
+        assert_eq!(engine.items["1"].y, 3);
+        assert_eq!(engine.grid.get(2, 2), Some(Some("0")));
+        assert_eq!(engine.grid.get(0, 3), Some(Some("1")));
+
+        assert!(matches!(
+            engine.resize_item("0", 0, 1),
+            Err(GridEngineError::Item(ItemError::InvalidDimensions {
+                w: 0,
+                h: 1
+            }))
+        ));
+        assert!(matches!(
+            engine.resize_item("0", 11, 1),
+            Err(GridEngineError::Item(ItemError::TooWide {
+                w: 11,
+                cols: 10
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_resize_item_shrinks() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 3, 3).unwrap();
+
+        engine.resize_item("0", 1, 1).unwrap();
+        assert_eq!(engine.grid.get(0, 0), Some(Some("0")));
+        assert_eq!(engine.grid.get(2, 2), Some(None));
+        assert_eq!(engine.items["0"].w, 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_change_serde_round_trip() {
+        let node = Node::new("0", 0, 0, 2, 2);
+        let changes = vec![
+            Change::Add(AddChangeData::new(node.clone())),
+            Change::Remove(RemoveChangeData::new(node.clone())),
+            Change::Move(MoveChangeData::new(node.clone(), node.with_position(4, 4))),
+            Change::Resize(ResizeChangeData::new(
+                node.clone(),
+                Node::new("0", 0, 0, 3, 3),
+            )),
+        ];
+
+        for change in changes {
+            let json = serde_json::to_string(&change).unwrap();
+            let parsed: Change = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, change);
+        }
+
+        let json = serde_json::to_value(Change::Add(AddChangeData::new(node))).unwrap();
+        assert_eq!(json["type"], "Add");
+        assert_eq!(json["value"]["id"], "0");
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 0, 0, 2, 2).unwrap();
+        engine.move_item("0", 1, 1).unwrap();
+        assert!(engine.validate().is_ok());
+
+        // Corrupt the grid behind the engine's back
+        let corrupt = |engine: &mut GridEngine, x: usize, y: usize, value: Option<&str>| {
+            engine.grid.set(x, y, value).unwrap();
+        };
+        let (x, y) = (engine.items["1"].x, engine.items["1"].y);
+        corrupt(&mut engine, x, y, None);
+        corrupt(&mut engine, 9, 9, Some("ghost"));
+        corrupt(&mut engine, 8, 9, Some("1"));
+
+        let reports = engine.validate().unwrap_err();
+        assert_eq!(reports.len(), 3);
+        assert!(reports.contains(&InconsistencyReport::UnknownItem {
+            id: "ghost".to_string(),
+            x: 9,
+            y: 9
+        }));
+        assert!(reports.contains(&InconsistencyReport::StrayCell {
+            id: "1".to_string(),
+            x: 8,
+            y: 9
+        }));
+        assert!(reports.contains(&InconsistencyReport::MissingCell {
+            id: "1".to_string(),
+            x,
+            y,
+            found: None
+        }));
+    }
+
+    #[test]
+    fn test_freeze() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        let before = engine.snapshot();
+
+        engine.freeze();
+        assert!(engine.is_frozen());
+        assert!(matches!(
+            engine.add_item("1".to_string(), 0, 0, 2, 2),
+            Err(GridEngineError::Frozen)
+        ));
+        assert!(matches!(
+            engine.move_item("0", 4, 4),
+            Err(GridEngineError::Frozen)
+        ));
+        assert!(matches!(
+            engine.remove_item("0"),
+            Err(GridEngineError::Frozen)
+        ));
+        assert!(matches!(engine.insert_row(0), Err(GridEngineError::Frozen)));
+        assert!(matches!(
+            engine.reserve_rows(50),
+            Err(GridEngineError::Frozen)
+        ));
+        assert!(matches!(
+            engine.trim_to_content(),
+            Err(GridEngineError::Frozen)
+        ));
+        assert!(matches!(engine.repair(), Err(GridEngineError::Frozen)));
+        assert!(matches!(
+            engine.set_item_weight("0", 5),
+            Err(GridEngineError::Frozen)
+        ));
+        assert!(matches!(
+            engine.restore(before.clone()),
+            Err(GridEngineError::Frozen)
+        ));
+        assert_eq!(engine.snapshot(), before);
+        assert!(engine.pending_changes.is_empty());
+
+        // Reads still work
+        assert_eq!(engine.get_nodes().len(), 1);
+        assert!(engine.is_region_free(4, 4, 2, 2));
+
+        engine.thaw();
+        engine.move_item("0", 4, 4).unwrap();
+        assert_eq!(engine.items["0"].x, 4);
+    }
+
+    #[test]
+    fn test_move_many_swaps_group() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 6, 6, 2, 2).unwrap();
+        engine.add_item("2".to_string(), 6, 4, 2, 2).unwrap();
+
+        let changes = engine.move_many(&[("0", 6, 6), ("1", 0, 0)]).unwrap();
+        assert_eq!(changes.len(), 2);
+
+        assert_eq!((engine.items["0"].x, engine.items["0"].y), (6, 6));
+        assert_eq!((engine.items["1"].x, engine.items["1"].y), (0, 0));
+        // Untouched by the group
+        assert_eq!((engine.items["2"].x, engine.items["2"].y), (6, 4));
+        assert!(engine.validate().is_ok());
+
+        // The weights are left as they were
+        assert_eq!(engine.items["0"].weight, 1);
+    }
+
+    #[test]
+    fn test_move_many_displaces_others() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 2, 0, 2, 2).unwrap();
+        engine.add_item("2".to_string(), 0, 4, 4, 2).unwrap();
+
+        engine.move_many(&[("0", 0, 3), ("1", 2, 3)]).unwrap();
+        assert_eq!((engine.items["0"].x, engine.items["0"].y), (0, 3));
+        assert_eq!((engine.items["1"].x, engine.items["1"].y), (2, 3));
+        assert_eq!((engine.items["2"].x, engine.items["2"].y), (0, 5));
+        assert!(engine.validate().is_ok());
+
+        // Overlapping targets and unknown ids are rejected
+        let before = engine.snapshot();
+        assert!(matches!(
+            engine.move_many(&[("0", 0, 0), ("1", 1, 0)]),
+            Err(GridEngineError::Item(ItemError::PositionOccupied { .. }))
+        ));
+        assert!(engine.move_many(&[("0", 0, 0), ("missing", 4, 0)]).is_err());
+        assert_eq!(engine.snapshot(), before);
+    }
+
+    #[test]
+    fn test_add_placement() {
+        let mut engine = GridEngine::new(10, 10);
+        assert_eq!(engine.add_placement(), AddPlacement::PushExisting);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 0, 0, 2, 2).unwrap();
+        assert_eq!(engine.items["1"].y, 0);
+        assert_eq!(engine.items["0"].y, 2);
+
+        let mut engine = GridEngine::new_with_options(GridOptions {
+            add_placement: AddPlacement::FindNextFree,
+            ..GridOptions::new(10, 10)
+        });
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 1, 4, 2, 2).unwrap();
+        engine.add_item("2".to_string(), 0, 0, 2, 3).unwrap();
+        assert_eq!(engine.items["0"].y, 0);
+        assert_eq!(engine.items["1"].y, 4);
+        assert_eq!(engine.items["2"].y, 6);
+        assert!(engine.validate().is_ok());
+    }
+
+    #[cfg(feature = "strict-invariants")]
+    #[test]
+    fn test_strict_invariants_hold() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 0, 0, 3, 2).unwrap();
+        engine.add_item("2".to_string(), 1, 1, 2, 3).unwrap();
+        engine.move_item("0", 1, 0).unwrap();
+        engine.resize_item("1", 4, 4).unwrap();
+        engine.remove_item("2").unwrap();
+        assert!(engine.validate().is_ok());
+    }
+
+    #[test]
+    fn test_add_item_pushes_stacked_colliders_apart() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+        engine.add_item("1".to_string(), 0, 0, 3, 2).unwrap();
+        engine.add_item("2".to_string(), 1, 1, 2, 3).unwrap();
+
+        assert_eq!(engine.items["2"].y, 1);
+        assert_eq!((engine.items["0"].y, engine.items["1"].y), (4, 6));
+        assert!(engine.validate().is_ok());
+    }
+
+    #[test]
+    fn test_from_dense() {
+        let a = Some("a".to_string());
+        let mut dense = vec![vec![None; 4]; 4];
+        dense[1][2] = a.clone();
+        dense[1][3] = a.clone();
+        dense[2][2] = a.clone();
+        dense[2][3] = a;
+
+        let engine = GridEngine::from_dense(dense).unwrap();
+
+        assert_eq!(engine.items.len(), 1);
+        assert_eq!(engine.items["a"], Node::new("a".to_string(), 2, 1, 2, 2));
+        assert_eq!((engine.grid.rows(), engine.grid.cols()), (4, 4));
+        assert!(engine.validate().is_ok());
+    }
+
+    #[test]
+    fn test_from_dense_rejects_non_rectangular_items() {
+        let a = Some("a".to_string());
+        let dense = vec![vec![a.clone(), None], vec![a.clone(), a]];
+
+        let result = GridEngine::from_dense(dense);
+
+        assert!(matches!(
+            result,
+            Err(GridEngineError::Item(ItemError::NotRectangular { ref id })) if id == "a"
+        ));
+    }
+
+    #[test]
+    fn test_items_sorted_by_area_descending() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("small".to_string(), 0, 0, 1, 1).unwrap();
+        engine.add_item("large".to_string(), 2, 0, 3, 3).unwrap();
+        engine.add_item("medium".to_string(), 6, 0, 2, 2).unwrap();
+
+        let nodes = engine.items_sorted_by(|a, b| (b.w * b.h).cmp(&(a.w * a.h)));
+        let ids: Vec<&str> = nodes.iter().map(|n| n.id()).collect();
+
+        assert_eq!(ids, ["large", "medium", "small"]);
+    }
+
+    #[test]
+    fn test_move_item_outcome_separates_displaced_items() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0".to_string(), 0, 0, 4, 2).unwrap();
+        engine.add_item("1".to_string(), 4, 0, 2, 2).unwrap();
+        engine.add_item("2".to_string(), 6, 0, 2, 2).unwrap();
+
+        let outcome = engine.move_item("0", 4, 0).unwrap().unwrap();
+
+        assert_eq!(
+            outcome.moved(),
+            &Change::Move(MoveChangeData::new(
+                Node::new("0".to_string(), 0, 0, 4, 2),
+                Node::new("0".to_string(), 4, 0, 4, 2),
+            ))
+        );
+        let mut displaced: Vec<&str> = outcome
+            .displaced()
+            .iter()
+            .map(|change| match change {
+                Change::Move(data) => data.new_value().id(),
+                _ => panic!("Expected only moves"),
+            })
+            .collect();
+        displaced.sort();
+        assert_eq!(displaced, ["1", "2"]);
+        assert!(engine.move_item("0", 4, 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_compact_horizontal_fills_gap() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(4, 10);
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
+        engine.add_item("1", 2, 0, 2, 2).unwrap();
+        engine.add_item("2", 4, 0, 2, 2).unwrap();
+        engine.remove_item("1").unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |event| events_clone.lock().unwrap().push(event.clone()))
+            .unwrap();
+
+        engine.compact_horizontal().unwrap();
+
+        assert_eq!((engine.items["0"].x, engine.items["2"].x), (0, 2));
+        assert_eq!(events.lock().unwrap().len(), 1);
+        assert_eq!(events.lock().unwrap()[0].changes().len(), 1);
+
+        engine.compact_horizontal().unwrap();
+        assert_eq!(events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_preview_move_matches_real_move() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
+        engine.add_item("1", 0, 2, 2, 2).unwrap();
+        engine.add_item("2", 0, 4, 2, 2).unwrap();
+        let before = engine.items.clone();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |event| events_clone.lock().unwrap().push(event.clone()))
+            .unwrap();
+
+        let preview = engine.preview_move("0", 0, 3).unwrap();
+        assert_eq!(engine.items, before);
+        assert!(events.lock().unwrap().is_empty());
+
+        engine.move_item("0", 0, 3).unwrap();
+        assert_eq!(events.lock().unwrap()[0].changes(), &preview);
+    }
+
+    #[test]
+    fn test_replace_item() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("placeholder", 1, 2, 3, 4).unwrap();
+        engine.set_item_weight("placeholder", 5).unwrap();
+        engine.add_item("other", 6, 0, 1, 1).unwrap();
+
+        engine
+            .replace_item("placeholder", "content".to_string())
+            .unwrap();
+
+        assert!(!engine.items.contains_key("placeholder"));
+        let content = &engine.items["content"];
+        assert_eq!((content.x, content.y, content.w, content.h), (1, 2, 3, 4));
+        assert_eq!((content.weight, content.seq), (1, 2));
+        assert_eq!(engine.grid.get(1, 2), Some(Some("content")));
+        assert!(engine.validate().is_ok());
+
+        assert!(engine.replace_item("missing", "x".to_string()).is_err());
+        assert!(engine.replace_item("content", "other".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_peek_pending_reflects_queued_changes() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
+        assert!(engine.peek_pending().is_empty());
+
+        let node = engine.items["0"].clone();
+        engine.create_remove_change(&node);
+        engine.create_add_change(Node::new("1".to_string(), 4, 4, 1, 1));
+
+        assert_eq!(
+            engine.peek_pending(),
+            [
+                Change::Remove(RemoveChangeData::new(node)),
+                Change::Add(AddChangeData::new(Node::new("1".to_string(), 4, 4, 1, 1))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_ids() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("a", 0, 0, 2, 2).unwrap();
+        engine.add_item("b", 2, 0, 1, 3).unwrap();
+        engine.add_item("c", 0, 4, 3, 1).unwrap();
+
+        engine.map_ids(|id| format!("w_{id}")).unwrap();
+
+        let ids: Vec<&String> = engine.items.keys().collect();
+        assert_eq!(ids, ["w_a", "w_b", "w_c"]);
+        assert_eq!(engine.grid.get(1, 1), Some(Some("w_a")));
+        assert_eq!(engine.grid.get(2, 2), Some(Some("w_b")));
+        assert_eq!(engine.grid.get(2, 4), Some(Some("w_c")));
+        assert!(engine.validate().is_ok());
+    }
+
+    #[test]
+    fn test_map_ids_rejects_duplicates() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("a1", 0, 0, 2, 2).unwrap();
+        engine.add_item("a2", 2, 0, 2, 2).unwrap();
+        let before = engine.items.clone();
+
+        let result = engine.map_ids(|id| id[..1].to_string());
+
+        assert!(matches!(
+            result,
+            Err(GridEngineError::Item(ItemError::ItemAlreadyExists { ref id })) if id == "a"
+        ));
+        assert_eq!(engine.items, before);
+        assert_eq!(engine.grid.get(0, 0), Some(Some("a1")));
+    }
+
+    #[test]
+    fn test_stack_in_column_strategy() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.set_collision_strategy(CollisionStrategy::StackInColumn);
+        engine.add_item("lane_a", 0, 0, 2, 2).unwrap();
+        engine.add_item("lane_b", 2, 0, 2, 2).unwrap();
+
+        // Overlaps both lanes, so it's stacked below them
+        engine.add_item("wide", 1, 1, 2, 2).unwrap();
+        assert_eq!(engine.items["lane_a"].y, 0);
+        assert_eq!(engine.items["lane_b"].y, 0);
+        assert_eq!(engine.items["wide"].y, 2);
+
+        // Same lane as lane_a, which is pushed down and stacked below wide
+        engine.add_item("top_a", 0, 0, 2, 1).unwrap();
+        assert_eq!(engine.items["lane_b"].y, 0);
+        assert_eq!(engine.items["wide"].y, 2);
+        assert_eq!(engine.items["lane_a"].y, 4);
+        assert_eq!(engine.items["top_a"].y, 0);
+        assert!(engine.validate().is_ok());
+    }
+
+    #[test]
+    fn test_get_grid_formatted_compact() {
+        let mut engine = GridEngine::new(3, 4);
+        engine.add_item("zeta", 2, 0, 2, 1).unwrap();
+        engine.add_item("alpha", 0, 1, 1, 2).unwrap();
+        engine.add_item("mid", 1, 2, 1, 1).unwrap();
+
+        let formatted = engine.get_grid_formatted_compact();
+
+        assert_eq!(
+            formatted,
+            "[ ][ ][0][0]\n[1][ ][ ][ ]\n[1][2][ ][ ]\n\n0: zeta\n1: alpha\n2: mid\n"
+        );
+        assert_eq!(engine.get_grid_formatted_compact(), formatted);
+    }
+
+    #[test]
+    fn test_repair_clears_orphan_cells() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("0", 0, 0, 2, 1).unwrap();
+        engine.grid.set(3, 2, Some("ghost")).unwrap();
+        engine.grid.set(3, 3, Some("ghost")).unwrap();
+        assert!(engine.validate().is_err());
+
+        assert_eq!(engine.repair().unwrap(), 2);
+
+        assert!(engine.validate().is_ok());
+        assert_eq!(engine.grid.get(0, 0), Some(Some("0")));
+        assert_eq!(engine.repair().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_trim_to_content_defaults_to_initial_rows() {
+        let mut engine = GridEngine::new(10, 4);
+        assert_eq!(engine.min_rows(), 10);
+
+        engine.add_item("0", 0, 20, 2, 2).unwrap();
+        engine.remove_item("0").unwrap();
+        assert_eq!(engine.grid.rows(), 22);
+
+        engine.trim_to_content().unwrap();
+        assert_eq!(engine.grid.rows(), 10);
+    }
+
+    #[test]
+    fn test_add_items_is_all_or_nothing() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |event| events_clone.lock().unwrap().push(event.clone()))
+            .unwrap();
+
+        let result = engine.add_items(vec![
+            ("0".to_string(), 0, 0, 2, 2),
+            ("1".to_string(), 2, 0, 2, 2),
+            ("0".to_string(), 4, 0, 2, 2),
+        ]);
+
+        assert!(matches!(
+            result,
+            Err(GridEngineError::Item(ItemError::ItemAlreadyExists { ref id })) if id == "0"
+        ));
+        assert!(engine.is_empty());
+        assert!(engine.grid.iter_cells().all(|(_, _, cell)| cell.is_none()));
+        assert!(events.lock().unwrap().is_empty());
+
+        let changes = engine
+            .add_items(vec![
+                ("0".to_string(), 0, 0, 2, 2),
+                ("1".to_string(), 0, 0, 2, 2),
+            ])
+            .unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!((engine.items["0"].y, engine.items["1"].y), (2, 0));
+        assert_eq!(events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_typed_coordinates() {
+        let mut engine = GridEngine::new(10, 10);
+        engine
+            .add_item_at("0", Position::new(1, 2), Size::new(3, 1))
+            .unwrap();
+        engine.add_item_at("1", (5, 5), (1, 1)).unwrap();
+
+        assert_eq!(
+            (engine.items["0"].x, engine.items["0"].y),
+            (1, 2),
+            "x is the column and y the row"
+        );
+        assert_eq!((engine.items["0"].w, engine.items["0"].h), (3, 1));
+
+        engine.move_item_to("1", Position::new(7, 0)).unwrap();
+        assert_eq!(engine.items["1"].position(), Position::new(7, 0));
+    }
+
+    #[test]
+    fn test_nearest_free_cell() {
+        let mut engine = GridEngine::new(5, 5);
+        engine.add_item("0", 2, 2, 1, 1).unwrap();
+
+        assert_eq!(engine.nearest_free_cell(2, 2, 3), Some((2, 1)));
+        assert_eq!(engine.nearest_free_cell(1, 1, 3), Some((1, 1)));
+
+        engine.add_item("1", 0, 0, 5, 5).unwrap();
+        assert_eq!(engine.nearest_free_cell(2, 2, 3), None);
+    }
+
+    #[test]
+    fn test_bring_to_front_and_send_to_back() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0", 0, 0, 1, 1).unwrap();
+        engine.add_item("1", 1, 0, 1, 1).unwrap();
+        engine.add_item("2", 2, 0, 1, 1).unwrap();
+        let creation_order = |engine: &GridEngine| {
+            engine
+                .get_nodes_by_creation()
+                .iter()
+                .map(|node| node.id.clone())
+                .collect::<Vec<String>>()
+        };
+
+        engine.bring_to_front("0").unwrap();
+        assert_eq!(creation_order(&engine), ["1", "2", "0"]);
+        assert_eq!((engine.items["0"].x, engine.items["0"].y), (0, 0));
+
+        engine.send_to_back("2").unwrap();
+        assert_eq!(creation_order(&engine), ["2", "1", "0"]);
+
+        engine.add_item("3", 3, 0, 1, 1).unwrap();
+        engine.send_to_back("0").unwrap();
+        engine.send_to_back("0").unwrap();
+        assert_eq!(creation_order(&engine), ["0", "2", "1", "3"]);
+        assert!(engine.validate().is_ok());
+    }
+
+    #[test]
+    fn test_will_collides_with_does_not_expand_grid() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
+        let node = engine.items["0"].clone();
+
+        let collisions = engine
+            .will_collides_with(&node, 0, 8, &engine.grid)
+            .unwrap();
+        assert!(collisions.is_empty());
+        assert_eq!(engine.grid.rows(), 4);
+
+        engine.set_expand_y(false);
+        assert!(matches!(
+            engine.will_collides_with(&node, 0, 3, &engine.grid),
+            Err(InnerGridError::OutOfBoundsAccess {
+                x: 0,
+                y: 4,
+                axis: Axis::Y
+            })
+        ));
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("b", 4, 0, 1, 3).unwrap();
+        engine.add_item("a", 0, 1, 2, 2).unwrap();
+        engine.add_item("c,d", 6, 6, 1, 1).unwrap();
+
+        let csv = engine.export_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines,
+            ["id,x,y,w,h", "a,0,1,2,2", "b,4,0,1,3", "\"c,d\",6,6,1,1"]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_size_hints_serde_round_trip() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.set_row_heights(vec![120, 40, 40]);
+        engine.set_col_widths(vec![200]);
+
+        let json = serde_json::to_string(&engine.snapshot()).unwrap();
+        let snapshot: GridSnapshot = serde_json::from_str(&json).unwrap();
+
+        let mut restored = GridEngine::new(4, 4);
+        restored.restore(snapshot).unwrap();
+        assert_eq!(restored.row_heights(), [120, 40, 40]);
+        assert_eq!(restored.col_widths(), [200]);
+    }
+
+    #[test]
+    fn test_count_collisions_at() {
+        let mut engine = GridEngine::new(10, 4);
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
+        engine.add_item("1", 2, 0, 2, 2).unwrap();
+
+        assert_eq!(engine.count_collisions_at(1, 0, 2, 3), 2);
+        assert_eq!(engine.count_collisions_at(0, 0, 1, 1), 1);
+        assert_eq!(engine.count_collisions_at(0, 4, 4, 4), 0);
+        assert_eq!(engine.count_collisions_at(3, 1, 5, 1), 1);
+        assert_eq!(engine.grid.cols(), 4);
+    }
+
+    #[test]
+    fn test_defragment_clusters_items_at_origin() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(12, 6);
+        engine.add_item("0", 4, 9, 1, 1).unwrap();
+        engine.add_item("1", 0, 5, 2, 2).unwrap();
+        engine.add_item("2", 3, 2, 3, 1).unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let captured = events.clone();
+        engine
+            .add_changes_listener(move |event| {
+                captured.lock().unwrap().push(event.changes().len());
+            })
+            .unwrap();
+
+        engine.defragment().unwrap();
+
+        let position = |id: &str| (engine.items[id].x, engine.items[id].y);
+        assert_eq!(position("1"), (0, 0));
+        assert_eq!(position("2"), (2, 0));
+        assert_eq!(position("0"), (5, 0));
+        assert_eq!(*events.lock().unwrap(), vec![3]);
+        assert!(engine.validate().is_ok());
+
+        engine.defragment().unwrap();
+        assert_eq!(events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_set_cols_reflows_without_losing_items() {
+        let mut engine = GridEngine::new(4, 12);
+        engine.add_item("0", 0, 0, 4, 1).unwrap();
+        engine.add_item("1", 4, 0, 4, 1).unwrap();
+        engine.add_item("2", 8, 0, 4, 2).unwrap();
+        engine.add_item("3", 0, 1, 6, 1).unwrap();
+
+        engine.set_cols(4).unwrap();
+
+        assert_eq!(engine.grid.cols(), 4);
+        assert_eq!(engine.items.len(), 4);
+        let placement = |id: &str| {
+            let node = &engine.items[id];
+            (node.x, node.y, node.w, node.h)
+        };
+        assert_eq!(placement("0"), (0, 0, 4, 1));
+        assert_eq!(placement("1"), (0, 1, 4, 1));
+        assert_eq!(placement("2"), (0, 2, 4, 2));
+        assert_eq!(placement("3"), (0, 4, 4, 1));
+        assert!(engine.validate().is_ok());
+
+        engine.set_cols(8).unwrap();
+        assert_eq!(engine.grid.cols(), 8);
+        assert_eq!((engine.items["3"].x, engine.items["3"].y), (0, 4));
+    }
+
+    #[test]
+    fn test_change_apply_to_mirrors_engine() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        let mut view = engine.snapshot();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        engine
+            .add_changes_listener(move |event| {
+                received_clone
+                    .lock()
+                    .unwrap()
+                    .extend(event.changes().clone());
+            })
+            .unwrap();
+
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
+        engine.move_item("0", 4, 1).unwrap();
+
+        let changes = received.lock().unwrap().clone();
+        assert!(matches!(
+            changes.as_slice(),
+            [Change::Add(_), Change::Move(_)]
+        ));
+        for change in &changes {
+            change.apply_to(&mut view).unwrap();
+        }
+        assert_eq!(view, engine.snapshot());
+
+        // A change that doesn't fit leaves the view untouched
+        let wide = Change::Add(AddChangeData::new(Node::new("1", 9, 0, 2, 1)));
+        assert!(wide.apply_to(&mut view).is_err());
+        assert_eq!(view, engine.snapshot());
+    }
+
+    #[test]
+    fn test_items_overlapping_detached_node() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
+        engine.add_item("1", 2, 0, 2, 2).unwrap();
+        engine.add_item("2", 6, 6, 1, 1).unwrap();
+
+        let ids = |ghost: &Node| {
+            engine
+                .items_overlapping(ghost)
+                .iter()
+                .map(|node| node.id.clone())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(ids(&Node::new("ghost", 1, 1, 2, 2)), ["0", "1"]);
+        assert_eq!(ids(&Node::new("1", 1, 1, 2, 2)), ["0"]);
+        assert!(ids(&Node::new("ghost", 4, 4, 2, 2)).is_empty());
+        assert_eq!(ids(&Node::new("ghost", 6, 6, 10, 1)), ["2"]);
+    }
+
+    #[test]
+    fn test_whole_grid_scans_match_serial_results() {
+        let mut engine = GridEngine::new(500, 500);
+        for index in 0..100 {
+            let (x, y) = ((index % 10) * 50, (index / 10) * 50);
+            engine
+                .add_item(
+                    index.to_string(),
+                    x + index % 3,
+                    y,
+                    1 + index % 7,
+                    1 + index % 5,
+                )
+                .unwrap();
+        }
+        engine.grid.set(499, 499, Some("ghost")).unwrap();
+        engine.grid.set(0, 0, None).unwrap();
+
+        let dense: Vec<Vec<Option<String>>> = (0..engine.grid.rows())
+            .map(|y| {
+                (0..engine.grid.cols())
+                    .map(|x| engine.grid.peek(x, y).unwrap().map(str::to_string))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(engine.to_dense(), dense);
+
+        let occupied: Vec<(usize, usize)> = engine
+            .grid
+            .iter_cells()
+            .filter(|(_, _, cell)| cell.is_some())
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        let stats = engine.occupancy();
+        assert_eq!(stats.occupied_cells, occupied.len());
+        let min_x = occupied.iter().map(|(x, _)| *x).min().unwrap();
+        let max_x = occupied.iter().map(|(x, _)| *x).max().unwrap();
+        let max_y = occupied.iter().map(|(_, y)| *y).max().unwrap();
+        assert_eq!(
+            stats.bounding_box,
+            Some((min_x, 0, max_x - min_x + 1, max_y + 1))
+        );
+
+        assert_eq!(
+            engine.validate(),
+            Err(vec![
+                InconsistencyReport::UnknownItem {
+                    id: "ghost".to_string(),
+                    x: 499,
+                    y: 499,
+                },
+                InconsistencyReport::MissingCell {
+                    id: "0".to_string(),
+                    x: 0,
+                    y: 0,
+                    found: None,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_clone_region_and_paste_elsewhere() {
+        let mut engine = GridEngine::new(12, 12);
+        engine.add_item("0", 1, 1, 2, 2).unwrap();
+        engine.add_item("1", 3, 2, 1, 2).unwrap();
+        engine.add_item("2", 3, 4, 2, 2).unwrap();
+
+        let copied = engine.clone_region(1, 1, 3, 3);
+        let placement = |node: &Node| (node.id.clone(), node.x, node.y, node.w, node.h);
+        assert_eq!(
+            copied.iter().map(placement).collect::<Vec<_>>(),
+            [("0".to_string(), 0, 0, 2, 2), ("1".to_string(), 2, 1, 1, 2)]
+        );
+
+        let ids = engine.paste_region(copied.clone(), 6, 6).unwrap();
+        assert_eq!(ids, ["0-copy", "1-copy"]);
+        assert_eq!(
+            placement(&engine.items["0-copy"]),
+            ("0-copy".to_string(), 6, 6, 2, 2)
+        );
+        assert_eq!(
+            placement(&engine.items["1-copy"]),
+            ("1-copy".to_string(), 8, 7, 1, 2)
+        );
+        assert_eq!(placement(&engine.items["0"]), ("0".to_string(), 1, 1, 2, 2));
+
+        let ids = engine.paste_region(copied, 0, 9).unwrap();
+        assert_eq!(ids, ["0-copy-2", "1-copy-2"]);
+        assert_eq!(engine.items.len(), 7);
+        assert!(engine.validate().is_ok());
+    }
+
+    #[test]
+    fn test_stack_strategy_keeps_overlapping_items() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.set_collision_strategy(CollisionStrategy::Stack);
+        engine.add_item("0", 0, 0, 3, 3).unwrap();
+        engine.add_item("1", 1, 1, 3, 3).unwrap();
+        engine.add_item("2", 2, 2, 1, 1).unwrap();
+
+        let placement = |engine: &GridEngine, id: &str| {
+            let node = &engine.items[id];
+            (node.x, node.y)
+        };
+        assert_eq!(engine.get_nodes().len(), 3);
+        assert_eq!(placement(&engine, "0"), (0, 0));
+        assert_eq!(placement(&engine, "1"), (1, 1));
+        assert_eq!(engine.item_at(2, 2).unwrap().id, "2");
+        assert_eq!(engine.stacked[&(2, 2)], ["0", "1"]);
+        assert!(engine.validate().is_ok());
+
+        // Moving the top item away reveals the ones below
+        engine.move_item("2", 8, 8).unwrap();
+        assert_eq!(engine.item_at(2, 2).unwrap().id, "1");
+        engine.remove_item("1").unwrap();
+        assert_eq!(engine.item_at(2, 2).unwrap().id, "0");
+        assert!(engine.stacked.is_empty());
+
+        engine.add_item("3", 0, 0, 1, 1).unwrap();
+        engine.bring_to_front("0").unwrap();
+        assert_eq!(engine.item_at(0, 0).unwrap().id, "0");
+        assert_eq!(placement(&engine, "3"), (0, 0));
+        assert!(engine.validate().is_ok());
+    }
+
+    #[test]
+    fn test_collisions_resolve_in_layout_order() {
+        let layout = |ids: [&str; 3]| {
+            let mut engine = GridEngine::new(10, 10);
+            engine.add_item(ids[0], 2, 0, 2, 1).unwrap();
+            engine.add_item(ids[1], 0, 1, 2, 1).unwrap();
+            engine.add_item(ids[2], 1, 2, 2, 2).unwrap();
+            engine.add_item("incoming", 0, 0, 4, 3).unwrap();
+
+            [ids[0], ids[1], ids[2]].map(|id| {
+                let node = &engine.items[id];
+                (node.x, node.y)
+            })
+        };
+
+        let expected = [(2, 3), (0, 3), (1, 4)];
+        assert_eq!(layout(["a", "b", "c"]), expected);
+        assert_eq!(layout(["c", "b", "a"]), expected);
+        assert_eq!(layout(["b", "c", "a"]), expected);
+    }
+
+    #[test]
+    fn test_toggle_item_twice_leaves_grid_empty() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = GridEngine::new(10, 10);
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let captured = events.clone();
+        engine
+            .add_changes_listener(move |event| {
+                captured.lock().unwrap().extend(event.changes().clone());
+            })
+            .unwrap();
+
+        match engine.toggle_item("0", 1, 1, 2, 2).unwrap() {
+            Toggled::Added(node) => assert_eq!((node.x, node.y), (1, 1)),
+            Toggled::Removed(_) => panic!("expected the item to be added"),
+        }
+        match engine.toggle_item("0", 5, 5, 1, 1).unwrap() {
+            Toggled::Removed(node) => assert_eq!((node.x, node.y, node.w), (1, 1, 2)),
+            Toggled::Added(_) => panic!("expected the item to be removed"),
+        }
+
+        assert!(engine.is_empty());
+        assert!(matches!(
+            events.lock().unwrap().as_slice(),
+            [Change::Add(_), Change::Remove(_)]
+        ));
+    }
+
+    #[test]
+    fn test_item_gap_pushes_one_cell_further() {
+        let pushed_y = |gap: usize| {
+            let mut engine = GridEngine::new(10, 10);
+            engine.set_item_gap(gap);
+            engine.add_item("0", 0, 1, 2, 2).unwrap();
+            engine.add_item("1", 0, 0, 2, 2).unwrap();
+            engine.items["0"].y
+        };
+
+        assert_eq!(pushed_y(0), 2);
+        assert_eq!(pushed_y(1), 3);
+    }
+
+    #[test]
+    fn test_item_gap_in_compact_horizontal() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.set_item_gap(1);
+        engine.add_item("0", 2, 0, 2, 1).unwrap();
+        engine.add_item("1", 7, 0, 2, 1).unwrap();
+        engine.add_item("2", 5, 1, 1, 1).unwrap();
+
+        engine.compact_horizontal().unwrap();
+
+        assert_eq!(engine.items["0"].x, 0);
+        assert_eq!(engine.items["1"].x, 3);
+        assert_eq!(engine.items["2"].x, 0);
+        assert_eq!(engine.snapshot().item_gap(), 1);
+    }
+
+    #[test]
+    fn test_pack_places_every_item_without_overlap() {
+        let mut engine = GridEngine::new(2, 3);
+        engine.add_item("fixed", 1, 0, 1, 1).unwrap();
+
+        let changes = engine
+            .pack(vec![
+                ("a".to_string(), 1, 1),
+                ("b".to_string(), 3, 1),
+                ("c".to_string(), 2, 2),
+                ("d".to_string(), 1, 2),
+                ("e".to_string(), 1, 1),
+            ])
+            .unwrap();
+
+        assert_eq!(changes.len(), 5);
+        assert_eq!(engine.items.len(), 6);
+        assert_eq!((engine.items["fixed"].x, engine.items["fixed"].y), (1, 0));
+        let placement = |id: &str| (engine.items[id].x, engine.items[id].y);
+        assert_eq!(placement("c"), (0, 1));
+        assert_eq!(placement("b"), (0, 3));
+        assert_eq!(placement("d"), (2, 0));
+        assert_eq!(placement("a"), (0, 0));
+        assert_eq!(placement("e"), (2, 2));
+        assert!(engine.validate().is_ok());
+
+        assert!(engine.pack(vec![("a".to_string(), 1, 1)]).is_err());
+        assert!(engine.pack(vec![("f".to_string(), 4, 1)]).is_err());
+        assert_eq!(engine.items.len(), 6);
+    }
+
+    #[test]
+    fn test_snap_to_bounds_clamps_move_target() {
+        let mut engine = GridEngine::new(6, 12);
+        engine.add_item("0", 0, 0, 3, 2).unwrap();
+        assert!(engine.move_item("0", 10, 0).is_err());
+
+        engine.set_snap_to_bounds(true);
+        engine.move_item("0", 10, 0).unwrap();
+        assert_eq!((engine.items["0"].x, engine.items["0"].y), (9, 0));
+
+        // Already at the snapped position
+        assert!(engine.move_item("0", 11, 0).unwrap().is_none());
+
+        engine.set_expand_y(false);
+        engine.move_item("0", 0, 20).unwrap();
+        assert_eq!((engine.items["0"].x, engine.items["0"].y), (0, 4));
+        assert_eq!(engine.grid.rows(), 6);
+    }
+
+    #[test]
+    fn test_snapshot_size_histogram() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
+        engine.add_item("1", 2, 0, 2, 2).unwrap();
+        engine.add_item("2", 4, 0, 1, 1).unwrap();
+
+        assert_eq!(
+            engine.snapshot().size_histogram(),
+            BTreeMap::from([((1, 1), 1), ((2, 2), 2)])
+        );
+        assert!(GridEngine::new(2, 2).snapshot().size_histogram().is_empty());
+    }
+
+    #[test]
+    fn test_shrink_to_fit_shrinks_incoming_item() {
+        let mut engine = GridEngine::new(6, 4);
+        engine.set_collision_strategy(CollisionStrategy::ShrinkToFit);
+        engine.add_item("top", 0, 0, 4, 1).unwrap();
+        engine.add_item("bottom", 0, 3, 4, 1).unwrap();
+
+        let node = engine.add_item("tall", 1, 1, 2, 4).unwrap();
+        assert_eq!((node.x, node.y, node.w, node.h), (1, 1, 2, 2));
+        assert_eq!(engine.items["bottom"].y, 3);
+
+        // Nothing fits at the target row, others are pushed instead
+        engine.add_item("blocked", 0, 3, 1, 1).unwrap();
+        assert_eq!(engine.items["blocked"].h, 1);
+        assert_eq!(engine.items["bottom"].y, 4);
+
+        // A capped grid shrinks the item instead of overflowing
+        engine.set_max_rows(Some(6));
+        engine.move_item("tall", 1, 5).unwrap();
+        let node = &engine.items["tall"];
+        assert_eq!((node.x, node.y, node.w, node.h), (1, 5, 2, 1));
+        assert!(engine.validate().is_ok());
+    }
+
+    #[test]
+    fn test_describe_summarizes_layout() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("b", 2, 0, 2, 1).unwrap();
+        engine.add_item("a", 0, 0, 2, 2).unwrap();
+
+        let summary = engine.describe();
+        assert_eq!((summary.rows, summary.cols), (4, 4));
+        assert_eq!(summary.item_count, 2);
+        assert_eq!(summary.occupancy_ratio, 0.375);
+        assert_eq!(
+            summary.items,
+            vec![
+                NodeSummary {
+                    id: "a".to_string(),
+                    x: 0,
+                    y: 0,
+                    w: 2,
+                    h: 2
+                },
+                NodeSummary {
+                    id: "b".to_string(),
+                    x: 2,
+                    y: 0,
+                    w: 2,
+                    h: 1
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_describe_serializes() {
+        let mut engine = GridEngine::new(2, 2);
+        engine.add_item("a", 0, 0, 1, 1).unwrap();
+
+        let json = serde_json::to_value(engine.describe()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "rows": 2,
+                "cols": 2,
+                "item_count": 1,
+                "occupancy_ratio": 0.25,
+                "items": [{ "id": "a", "x": 0, "y": 0, "w": 1, "h": 1 }],
+            })
+        );
+    }
 
-                    node.update_grid(&mut self.grid, UpdateGridOperation::Add)?;
+    #[test]
+    fn test_typed_listeners_filter_changes() {
+        use std::sync::{Arc, Mutex};
 
-                    self.items.insert(node.id.to_string(), node.clone());
-                }
-                Change::Remove(data) => {
-                    let node = &data.value;
+        let mut engine = GridEngine::new(10, 4);
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
 
-                    node.update_grid(&mut self.grid, UpdateGridOperation::Remove)?;
+        let calls: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let added = calls.clone();
+        engine
+            .on_add(move |node| {
+                added
+                    .lock()
+                    .unwrap()
+                    .push(format!("add {} {} {}", node.id, node.x, node.y))
+            })
+            .unwrap();
+        let removed = calls.clone();
+        engine
+            .on_remove(move |node| removed.lock().unwrap().push(format!("remove {}", node.id)))
+            .unwrap();
+        let moved = calls.clone();
+        engine
+            .on_move(move |old, new| {
+                moved
+                    .lock()
+                    .unwrap()
+                    .push(format!("move {} {} -> {}", new.id, old.y, new.y))
+            })
+            .unwrap();
 
-                    self.items.remove(&node.id);
-                }
-                Change::Move(data) => {
-                    let node = &data.new_value;
-                    let old_node = &data.old_value;
+        engine.add_item("1", 2, 1, 2, 1).unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec!["add 1 2 1"]);
 
-                    old_node.update_grid(&mut self.grid, UpdateGridOperation::Remove)?;
+        // Adding on top of "0" pushes it down, a single batch with both changes
+        calls.lock().unwrap().clear();
+        engine.add_item("2", 0, 0, 1, 1).unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec!["add 2 0 0", "move 0 0 -> 1"]);
 
-                    self.items.insert(node.id.to_string(), node.clone());
+        calls.lock().unwrap().clear();
+        engine.remove_item("2").unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec!["remove 2"]);
+    }
 
-                    node.update_grid(&mut self.grid, UpdateGridOperation::Add)?;
-                }
-            }
-        }
+    #[test]
+    fn test_footer_items_are_pushed_up() {
+        let mut engine = GridEngine::new(6, 4);
+        engine.set_footer_row(Some(4));
+        engine.add_item("footer", 0, 4, 4, 2).unwrap();
+        engine.add_item("top", 0, 0, 2, 1).unwrap();
 
-        self.events
-            .trigger_changes_event(&ChangesEventValue::new(changes.to_vec()));
-        Ok(())
-    }
+        engine.add_item("new", 0, 4, 2, 1).unwrap();
+        assert_eq!((engine.items["footer"].x, engine.items["footer"].y), (0, 2));
+        assert_eq!((engine.items["new"].x, engine.items["new"].y), (0, 4));
 
-    /// Returns a reference to the grid events system.
-    pub fn events(&self) -> &GridEvents {
-        &self.events
-    }
+        // Items above the footer are still pushed down
+        engine.add_item("above", 0, 0, 2, 1).unwrap();
+        assert_eq!(engine.items["top"].y, 1);
+        assert!(engine.validate().is_ok());
 
-    /// Returns a mutable reference to the grid events system.
-    pub fn events_mut(&mut self) -> &mut GridEvents {
-        &mut self.events
+        // No room above, the push fails and nothing changes
+        let mut engine = GridEngine::new(6, 4);
+        engine.set_footer_row(Some(4));
+        engine.add_item("footer", 0, 4, 4, 2).unwrap();
+        engine.add_item("blocker", 0, 0, 4, 3).unwrap();
+        let error = engine.add_item("late", 2, 4, 2, 1).unwrap_err();
+        assert!(matches!(
+            error,
+            GridEngineError::InnerGrid(InnerGridError::FooterPushBlocked { ref id }) if id == "footer"
+        ));
+        assert_eq!(engine.items["footer"].y, 4);
+        assert!(!engine.items.contains_key("late"));
+        assert!(engine.pending_changes.is_empty());
+        engine.add_item("low", 0, 6, 1, 1).unwrap();
+        assert!(engine.move_item("low", 2, 4).is_err());
+        assert_eq!(engine.items["low"].y, 6);
+        assert!(engine.pending_changes.is_empty());
     }
-}
 
-mod tests {
-    #[allow(unused_imports)]
-    use super::*;
+    #[test]
+    fn test_find_item_by() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("d", 0, 0, 3, 1).unwrap();
+        engine.add_item("a", 0, 1, 1, 1).unwrap();
+        engine.add_item("c", 0, 2, 2, 2).unwrap();
+        engine.add_item("b", 0, 4, 1, 3).unwrap();
+
+        let node = engine.find_item_by(|node| node.w >= 2).unwrap();
+        assert_eq!(node.id, "c");
+        assert!(engine.find_item_by(|node| node.w >= 4).is_none());
+    }
 
     #[test]
-    fn test_for_cell() {
-        let mut results = Vec::new();
-        let mut callback = |x: usize, y: usize| {
-            results.push((x, y));
-            Ok(())
-        };
+    fn test_relocate_offscreen_items() {
+        let mut engine = GridEngine::new(4, 12);
+        engine.add_item("kept", 0, 0, 3, 1).unwrap();
+        engine.add_item("far", 10, 0, 2, 2).unwrap();
+        engine.add_item("wide", 0, 2, 6, 1).unwrap();
+        assert!(engine.relocate_offscreen_items().unwrap().is_empty());
 
-        for_cell(
-            ForCellArgs {
-                x: 1,
-                y: 2,
-                w: 2,
-                h: 2,
-            },
-            &mut callback,
-        )
-        .unwrap();
+        // Shrink the columns behind the engine's back, without reflowing
+        engine.grid.resize_cols(4);
+        let changes = engine.relocate_offscreen_items().unwrap();
+        assert_eq!(changes.len(), 2);
 
-        assert_eq!(results, vec![(1, 2), (1, 3), (2, 2), (2, 3)]);
+        let node = &engine.items["far"];
+        assert_eq!((node.x, node.y, node.w, node.h), (0, 1, 2, 2));
+        let node = &engine.items["wide"];
+        assert_eq!((node.x, node.y, node.w, node.h), (0, 3, 4, 1));
+        assert_eq!((engine.items["kept"].x, engine.items["kept"].y), (0, 0));
+        assert!(engine.validate().is_ok());
     }
 
     #[test]
-    fn test_add_item() {
-        let mut engine = GridEngine::new(10, 10);
-        let item_0_id = engine
-            .add_item("0".to_string(), 0, 0, 2, 2)
-            .unwrap()
-            .id
-            .clone();
+    fn test_failed_resize_leaves_no_pending_changes() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.set_expand_y(false);
+        engine.add_item("r", 0, 0, 2, 1).unwrap();
+        engine.add_item("a", 0, 1, 1, 1).unwrap();
+        engine.add_item("b", 1, 1, 1, 3).unwrap();
 
-        assert!(engine.items.len() == 1);
-        for_cell(
-            ForCellArgs {
-                x: 0,
-                y: 0,
-                w: 2,
-                h: 2,
-            },
-            &mut |x, y| {
-                assert_eq!(engine.grid.get(x, y).unwrap().as_ref().unwrap(), &item_0_id);
-                Ok(())
-            },
-        )
-        .unwrap();
+        // "a" fits below the grown item, "b" doesn't, so the cascade fails halfway
+        assert!(engine.resize_item("r", 2, 2).is_err());
+        assert!(engine.pending_changes.is_empty());
+
+        engine.add_item("z", 3, 0, 1, 1).unwrap();
+        assert_eq!((engine.items["r"].w, engine.items["r"].h), (2, 1));
+        assert_eq!(engine.items["a"].y, 1);
+        assert_eq!(engine.items["b"].y, 1);
+        assert!(engine.validate().is_ok());
     }
 
     #[test]
-    fn test_add_item_handle_duplicated_id() {
-        let mut engine = GridEngine::new(10, 10);
-        engine.add_item("0".to_string(), 0, 0, 2, 2).unwrap();
+    fn test_failed_restore_item_state_leaves_no_pending_changes() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.set_expand_y(false);
+        engine.add_item("r", 0, 0, 2, 1).unwrap();
+        engine.add_item("a", 0, 1, 1, 1).unwrap();
+        engine.add_item("b", 1, 1, 1, 3).unwrap();
 
-        assert!(engine.add_item("0".to_string(), 0, 0, 2, 2).is_err())
+        let mut saved = engine.save_item_state("r").unwrap();
+        saved.h = 2;
+        assert!(engine.restore_item_state(saved).is_err());
+        assert!(engine.pending_changes.is_empty());
+
+        engine.add_item("z", 3, 0, 1, 1).unwrap();
+        assert_eq!(engine.items["r"].h, 1);
+        assert_eq!(engine.items["a"].y, 1);
+        assert!(engine.validate().is_ok());
     }
 
     #[test]
-    fn test_add_item_handle_collision() {
+    fn test_apply_external_rejects_overlaps() {
         let mut engine = GridEngine::new(10, 10);
-        let item_0_id = engine
-            .add_item("0".to_string(), 0, 0, 2, 2)
-            .unwrap()
-            .id
-            .clone();
-        let item_1_id = engine
-            .add_item("1".to_string(), 0, 0, 2, 2)
-            .unwrap()
-            .id
-            .clone();
+        engine.add_item("a", 0, 0, 2, 2).unwrap();
+        engine.add_item("b", 4, 0, 2, 2).unwrap();
 
-        // Item 0 should stay in position 0, 0
-        let item_0 = engine.items.get(&item_0_id).unwrap();
-        assert_eq!(item_0.x, 0);
-        assert_eq!(item_0.y, 2);
-        item_0
-            .for_cell(&mut |x, y| {
-                assert_eq!(engine.grid.get(x, y).unwrap().as_ref().unwrap(), &item_0_id);
-                Ok(())
-            })
-            .unwrap();
+        let error = engine
+            .apply_external(vec![Change::Add(AddChangeData::new(Node::new(
+                "c", 1, 1, 2, 2,
+            )))])
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            GridEngineError::Item(ItemError::PositionOccupied { ref ids }) if ids == &["a"]
+        ));
+        assert!(!engine.items.contains_key("c"));
 
-        // Item 1 should go to position 0, 2
-        let item_1 = engine.items.get(&item_1_id).unwrap();
-        assert_eq!(item_1.x, 0);
-        assert_eq!(item_1.y, 0);
-        item_1
-            .for_cell(&mut |x, y| {
-                assert_eq!(engine.grid.get(x, y).unwrap().as_ref().unwrap(), &item_1_id);
-                Ok(())
-            })
+        let b = engine.items["b"].clone();
+        let moved = MoveChangeData::new(b.clone(), b.with_position(1, 0));
+        let error = engine
+            .apply_external(vec![Change::Move(moved.clone())])
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            GridEngineError::Item(ItemError::PositionOccupied { ref ids }) if ids == &["a"]
+        ));
+        assert_eq!(engine.items["b"].x, 4);
+
+        // Moving "a" out of the way in the same batch makes room
+        let a = engine.items["a"].clone();
+        engine
+            .apply_external(vec![
+                Change::Move(MoveChangeData::new(a.clone(), a.with_position(0, 4))),
+                Change::Move(moved),
+            ])
             .unwrap();
+        assert_eq!((engine.items["b"].x, engine.items["a"].y), (1, 4));
+        assert!(engine.validate().is_ok());
     }
 
     #[test]
-    fn test_remove_item() {
-        let mut engine = GridEngine::new(10, 10);
-        let item_0_id = engine
-            .add_item("0".to_string(), 0, 0, 2, 3)
-            .unwrap()
-            .id
-            .clone();
-        engine.remove_item(&item_0_id).unwrap();
-        for_cell(
-            ForCellArgs {
-                x: 0,
-                y: 0,
+    fn test_add_rejects_zero_sized_items() {
+        let mut engine = GridEngine::new(4, 4);
+
+        assert!(matches!(
+            engine.add_item("a", 0, 0, 0, 2),
+            Err(GridEngineError::Item(ItemError::InvalidDimensions {
+                w: 0,
+                h: 2
+            }))
+        ));
+        assert!(matches!(
+            engine.try_add_item_strict("b", 0, 0, 2, 0),
+            Err(GridEngineError::Item(ItemError::InvalidDimensions {
                 w: 2,
-                h: 3,
-            },
-            &mut |x, y| {
-                let value = engine.grid.get(x, y).unwrap();
-                assert_eq!(value, &None);
-                Ok(())
-            },
-        )
-        .unwrap();
+                h: 0
+            }))
+        ));
+        assert!(matches!(
+            engine.add_items(vec![("c".to_string(), 0, 0, 0, 0)]),
+            Err(GridEngineError::Item(ItemError::InvalidDimensions {
+                w: 0,
+                h: 0
+            }))
+        ));
+        assert!(matches!(
+            engine.apply_external(vec![Change::Add(AddChangeData::new(Node::new(
+                "d".to_string(),
+                0,
+                0,
+                1,
+                0
+            )))]),
+            Err(GridEngineError::Item(ItemError::InvalidDimensions {
+                w: 1,
+                h: 0
+            }))
+        ));
+        assert_eq!(engine.total_items(), 0);
+        assert!(engine.pending_changes.is_empty());
     }
 
     #[test]
-    fn test_move_item() {
-        let mut engine = GridEngine::new(10, 10);
-        let item_0_id = engine
-            .add_item("0".to_string(), 0, 0, 2, 2)
-            .unwrap()
-            .id
-            .clone();
-        engine.move_item(&item_0_id, 1, 1).unwrap();
+    fn test_insert_row_needs_room_to_grow() {
+        let mut engine = GridEngine::new(3, 4);
+        engine.add_item("a", 0, 1, 1, 1).unwrap();
 
-        // Asserts that its present on the new position
-        for_cell(
-            ForCellArgs {
-                x: 1,
-                y: 1,
-                w: 2,
-                h: 2,
-            },
-            &mut |x, y| {
-                let item_on_expected_position = engine.grid.get(x, y).unwrap().as_ref().unwrap();
-                assert_eq!(item_on_expected_position, &item_0_id);
-                Ok(())
-            },
-        )
-        .unwrap();
+        engine.set_expand_y(false);
+        assert!(matches!(
+            engine.insert_row(0),
+            Err(GridEngineError::InnerGrid(
+                InnerGridError::OutOfBoundsAccess { axis: Axis::Y, .. }
+            ))
+        ));
 
-        // Asserts that its not present on the old position
-        for_cell(
-            ForCellArgs {
-                x: 0,
-                y: 0,
-                w: 1,
-                h: 1,
-            },
-            &mut |x, y| {
-                assert_eq!(engine.grid.get(x, y).unwrap(), &None);
-                Ok(())
-            },
-        )
-        .unwrap();
+        engine.set_expand_y(true);
+        engine.set_max_rows(Some(3));
+        assert!(matches!(
+            engine.insert_row(0),
+            Err(GridEngineError::InnerGrid(
+                InnerGridError::OutOfBoundsAccess { axis: Axis::Y, .. }
+            ))
+        ));
+        assert_eq!(engine.grid.rows(), 3);
+        assert_eq!(engine.items["a"].y, 1);
+        assert!(engine.pending_changes.is_empty());
     }
 
     #[test]
-    fn test_move_item_handle_collision() {
-        let mut engine = GridEngine::new(10, 10);
-        let item_0_id = engine
-            .add_item("0".to_string(), 0, 0, 2, 2)
-            .unwrap()
-            .id
-            .clone();
-        let item_1_id = engine
-            .add_item("1".to_string(), 0, 2, 2, 2)
-            .unwrap()
-            .id
-            .clone();
-        engine.move_item("0", 0, 1).unwrap();
+    fn test_insert_row_below_items_sends_no_event() {
+        use std::sync::{Arc, Mutex};
 
-        // Item 0 should go to position 0, 1
-        let item_0 = engine.items.get(&item_0_id).unwrap();
-        assert_eq!(item_0.x, 0);
-        assert_eq!(item_0.y, 1);
-        item_0
-            .for_cell(&mut |x, y| {
-                assert_eq!(engine.grid.get(x, y).unwrap().as_ref().unwrap(), &item_0_id);
-                Ok(())
-            })
-            .unwrap();
+        let mut engine = GridEngine::new(3, 4);
+        engine.add_item("a", 0, 0, 1, 1).unwrap();
 
-        // Item 1 should go to position 0, 3
-        let item_1 = engine.items.get(&item_1_id).unwrap();
-        assert_eq!(item_1.x, 0);
-        assert_eq!(item_1.y, 3);
-        item_1
-            .for_cell(&mut |x, y| {
-                assert_eq!(engine.grid.get(x, y).unwrap().as_ref().unwrap(), &item_1_id);
-                Ok(())
-            })
+        let events = Arc::new(Mutex::new(0));
+        let events_clone = events.clone();
+        engine
+            .events_mut()
+            .add_changes_listener(move |_| *events_clone.lock().unwrap() += 1)
             .unwrap();
+
+        engine.insert_row(2).unwrap();
+        assert_eq!(engine.grid.rows(), 4);
+        assert_eq!(*events.lock().unwrap(), 0);
     }
 
     #[test]
-    fn test_will_collides_with() {
-        let mut engine = GridEngine::new(10, 10);
-        let item_0_id = engine
-            .add_item("0".to_string(), 0, 0, 1, 2)
-            .unwrap()
-            .id
-            .clone();
+    fn test_set_item_weight_is_observable() {
+        use std::sync::{Arc, Mutex};
 
-        // Asserts that does not collide with self
-        assert!(
-            engine
-                .will_collides_with(
-                    engine.items.get(&item_0_id).unwrap(),
-                    0,
-                    0,
-                    &mut engine.grid.clone()
-                )
-                .unwrap()
-                .is_empty()
-        );
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("a", 0, 0, 2, 2).unwrap();
+        let before = engine.snapshot();
 
-        // Asserts that does not collide with empty position
-        assert!(
-            engine
-                .will_collides_with(
-                    engine.items.get(&item_0_id).unwrap(),
-                    2,
-                    2,
-                    &mut engine.grid.clone()
-                )
-                .unwrap()
-                .is_empty()
-        );
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        engine
+            .add_changes_listener(move |event| {
+                received_clone
+                    .lock()
+                    .unwrap()
+                    .extend(event.changes().clone());
+            })
+            .unwrap();
+        let moves = Arc::new(Mutex::new(0));
+        let moves_clone = moves.clone();
+        engine
+            .on_move(move |_, _| *moves_clone.lock().unwrap() += 1)
+            .unwrap();
 
-        // Asserts that collide with occupied position
-        engine.add_item("1".to_string(), 1, 2, 1, 2).unwrap();
+        engine.set_item_weight("a", 5).unwrap();
+        engine.set_item_weight("a", 5).unwrap();
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        let Change::Reorder(data) = &received[0] else {
+            panic!("expected a reorder, got {:?}", received[0]);
+        };
+        assert_eq!(data.new_value().weight, 5);
+        assert_eq!(*moves.lock().unwrap(), 0);
+        assert_eq!(engine.grid, before.grid);
 
-        // Full collision
-        assert!(
-            engine
-                .will_collides_with(
-                    engine.items.get(&item_0_id).unwrap(),
-                    1,
-                    2,
-                    &mut engine.grid.clone()
-                )
-                .unwrap()
-                .len()
-                == 1
-        );
+        // Coalesced events and snapshot diffs describe it the same way
+        let net = ChangesEventValue::new(received.clone()).net_changes();
+        assert_eq!(net.get("a"), Some(&received[0]));
+        assert_eq!(diff_items(&before.items, &engine.items), received);
 
-        // Partial collision
-        assert!(
-            engine
-                .will_collides_with(
-                    engine.items.get(&item_0_id).unwrap(),
-                    1,
-                    1,
-                    &mut engine.grid.clone()
-                )
-                .unwrap()
-                .len()
-                == 1
-        );
+        engine.add_pre_change_listener(|_| false).unwrap();
+        assert!(matches!(
+            engine.set_item_weight("a", 1),
+            Err(GridEngineError::ChangesVetoed)
+        ));
+        assert_eq!(engine.items["a"].weight, 5);
     }
 
     #[test]
-    fn test_get_nodes() {
-        let mut engine = GridEngine::new(10, 10);
-        let item_0_id = engine
-            .add_item("0".to_string(), 0, 0, 2, 2)
-            .unwrap()
-            .id
-            .clone();
-        let item_1_id = engine
-            .add_item("1".to_string(), 0, 2, 2, 2)
-            .unwrap()
-            .id
-            .clone();
+    fn test_restore_can_be_vetoed() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("a", 0, 0, 2, 2).unwrap();
+        let snapshot = engine.snapshot();
+        engine.move_item("a", 2, 2).unwrap();
+        let before = engine.snapshot();
 
-        let nodes = engine.get_nodes();
-        assert_eq!(nodes.len(), 2);
-        assert_eq!(nodes[0].id, item_0_id);
-        assert_eq!(nodes[1].id, item_1_id);
+        engine.add_pre_change_listener(|_| false).unwrap();
+        assert!(matches!(
+            engine.restore(snapshot),
+            Err(GridEngineError::ChangesVetoed)
+        ));
+        assert_eq!(engine.snapshot(), before);
+        assert_eq!((engine.items["a"].x, engine.items["a"].y), (2, 2));
+
+        // Nothing to veto when the layout is unchanged
+        engine.restore(before.clone()).unwrap();
+        assert_eq!(engine.snapshot(), before);
     }
 
     #[test]
-    fn test_move_result_will_not_collides_with_moving_item() {
-        let mut engine = GridEngine::new(10, 10);
-        engine.add_item("0".to_string(), 0, 0, 2, 3).unwrap();
-        engine.add_item("1".to_string(), 0, 6, 2, 2).unwrap();
-        engine.move_item("1", 0, 2).unwrap();
+    fn test_delete_empty_row_sends_no_event() {
+        use std::sync::{Arc, Mutex};
 
-        for_cell(
-            ForCellArgs {
-                x: 0,
-                y: 7,
-                w: 2,
-                h: 2,
-            },
-            &mut |x, y| {
-                let value = engine.grid.get(x, y).unwrap();
-                println!("value: {:?}", value);
-                assert_ne!(value, &Some("1".to_string()));
-                Ok(())
-            },
-        )
-        .unwrap();
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("a", 0, 0, 1, 1).unwrap();
+        engine.reserve_rows(6).unwrap();
+
+        let events = Arc::new(Mutex::new(0));
+        let events_clone = events.clone();
+        engine
+            .add_changes_listener(move |_| *events_clone.lock().unwrap() += 1)
+            .unwrap();
+        let vetoes = Arc::new(Mutex::new(0));
+        let vetoes_clone = vetoes.clone();
+        engine
+            .add_pre_change_listener(move |_| {
+                *vetoes_clone.lock().unwrap() += 1;
+                true
+            })
+            .unwrap();
+
+        assert!(engine.delete_row(3).unwrap().is_empty());
+        assert_eq!(engine.grid.rows(), 5);
+        assert_eq!(*events.lock().unwrap(), 0);
+        assert_eq!(*vetoes.lock().unwrap(), 0);
     }
 
     #[test]
-    fn test_node_movements_that_collides_twice_works() {
-        let mut engine = GridEngine::new(14, 10);
-        engine.add_item("0".to_string(), 1, 1, 2, 3).unwrap();
-        engine.add_item("1".to_string(), 2, 4, 2, 4).unwrap();
-        engine.add_item("2".to_string(), 0, 6, 2, 4).unwrap();
-        engine.move_item("2", 1, 2).unwrap();
+    fn test_delete_row_respects_min_rows() {
+        let mut engine = GridEngine::new(4, 4);
+        engine.add_item("a", 0, 1, 1, 1).unwrap();
 
-        println!("Items: {:#?}", engine.items);
+        engine.delete_row(0).unwrap();
+        assert_eq!(engine.items["a"].y, 0);
+        assert_eq!(engine.grid.rows(), 4);
 
-        engine.items.iter().for_each(|(_, node)| {
-            node.for_cell(&mut |x, y| {
-                let value = engine.grid.get(x, y).unwrap();
-                println!("Validating x: {}, y: {}", x, y);
-                assert_eq!(&Some(node.clone().id), value);
-                Ok(())
-            })
-            .unwrap();
-        });
+        engine.set_min_rows(2);
+        engine.delete_row(3).unwrap();
+        engine.delete_row(2).unwrap();
+        engine.delete_row(1).unwrap();
+        assert_eq!(engine.grid.rows(), 2);
+    }
+
+    #[test]
+    fn test_preview_move_on_frozen_engine() {
+        let mut engine = GridEngine::new(10, 10);
+        engine.add_item("0", 0, 0, 2, 2).unwrap();
+        engine.add_item("1", 0, 2, 2, 2).unwrap();
+        let before = engine.snapshot();
+
+        engine.freeze();
+        let preview = engine.preview_move("0", 0, 2).unwrap();
+        assert_eq!(preview.len(), 2);
+        assert_eq!(engine.snapshot(), before);
+        assert!(engine.pending_changes.is_empty());
     }
 }
@@ -41,9 +41,18 @@
 //! // The listener will be notified automatically
 //! ```
 
-use crate::{error::GridEventError, grid_engine::Change};
+use crate::{
+    error::GridEventError,
+    grid_engine::{
+        AddChangeData, Change, DispatchMode, MoveChangeData, RemoveChangeData, ReorderChangeData,
+        ResizeChangeData,
+    },
+    node::Node,
+};
 use std::{
+    collections::{BTreeMap, VecDeque},
     fmt::Debug,
+    panic::{self, AssertUnwindSafe},
     sync::{Arc, Mutex},
 };
 
@@ -52,6 +61,7 @@ use std::{
 /// This structure is passed to event listeners whenever changes occur in the grid,
 /// providing details about what changes were made.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChangesEventValue {
     /// Vector of changes that occurred in the grid
     changes: Vec<Change>,
@@ -71,6 +81,67 @@ impl ChangesEventValue {
     pub fn changes(&self) -> &Vec<Change> {
         &self.changes
     }
+
+    /// Collapses the changes into the net change of each item, keyed by item id.
+    ///
+    /// Only the state before the first change and after the last change of an
+    /// item are kept: two moves become a single move to the final position, an
+    /// add followed by a move becomes an add at the final position and a move
+    /// followed by a remove becomes a remove of the original node. Items whose
+    /// size changed overall end up as a resize, and items that only changed
+    /// their stacking order or weight as a reorder. Items that end up exactly as they
+    /// started are left out.
+    pub fn net_changes(&self) -> BTreeMap<String, Change> {
+        let mut states: BTreeMap<String, (Option<Node>, Option<Node>)> = BTreeMap::new();
+
+        for change in &self.changes {
+            let (id, before, after) = match change {
+                Change::Add(data) => (&data.value().id, None, Some(data.value())),
+                Change::Remove(data) => (&data.value().id, Some(data.value()), None),
+                Change::Move(data) => (
+                    &data.new_value().id,
+                    Some(data.old_value()),
+                    Some(data.new_value()),
+                ),
+                Change::Resize(data) => (
+                    &data.new_value().id,
+                    Some(data.old_value()),
+                    Some(data.new_value()),
+                ),
+                Change::Reorder(data) => (
+                    &data.new_value().id,
+                    Some(data.old_value()),
+                    Some(data.new_value()),
+                ),
+            };
+
+            states
+                .entry(id.to_string())
+                .or_insert_with(|| (before.cloned(), None))
+                .1 = after.cloned();
+        }
+
+        states
+            .into_iter()
+            .filter_map(|(id, state)| {
+                let change = match state {
+                    (None, Some(after)) => Change::Add(AddChangeData::new(after)),
+                    (Some(before), None) => Change::Remove(RemoveChangeData::new(before)),
+                    (Some(before), Some(after)) if before.w != after.w || before.h != after.h => {
+                        Change::Resize(ResizeChangeData::new(before, after))
+                    }
+                    (Some(before), Some(after)) if before.x != after.x || before.y != after.y => {
+                        Change::Move(MoveChangeData::new(before, after))
+                    }
+                    (Some(before), Some(after)) if before != after => {
+                        Change::Reorder(ReorderChangeData::new(before, after))
+                    }
+                    _ => return None,
+                };
+                Some((id, change))
+            })
+            .collect()
+    }
 }
 
 /// Type alias for change event listener functions.
@@ -79,25 +150,32 @@ impl ChangesEventValue {
 /// - Receive a reference to `ChangesEventValue`
 pub type ChangesEventFn = Box<dyn Fn(&ChangesEventValue) + Send + 'static + Sync>;
 
+/// Type alias for pre-change event listener functions.
+///
+/// These functions:
+/// - Receive a reference to `ChangesEventValue` holding the changes about to be applied
+/// - Return `false` to veto the whole batch
+pub type PreChangesEventFn = Box<dyn Fn(&ChangesEventValue) -> bool + Send + 'static + Sync>;
+
 /// Represents a registered event listener function.
 ///
 /// Each listener has a unique ID for management purposes and holds the actual
 /// callback function to be executed when changes occur.
-pub struct ListenerFunction {
+pub struct ListenerFunction<F = ChangesEventFn> {
     /// Unique identifier for the listener
     id: String,
     /// The callback function to execute when changes occur
-    function: ChangesEventFn,
+    function: F,
 }
 
-impl ListenerFunction {
+impl<F> ListenerFunction<F> {
     /// Creates a new `ListenerFunction` with the specified ID and function.
     ///
     /// # Arguments
     ///
     /// * `id` - Unique identifier for the listener
     /// * `function` - The callback function to execute when changes occur
-    pub fn new(id: impl Into<String>, function: ChangesEventFn) -> Self {
+    pub fn new(id: impl Into<String>, function: F) -> Self {
         Self {
             id: id.into(),
             function,
@@ -105,7 +183,7 @@ impl ListenerFunction {
     }
 }
 
-impl Debug for ListenerFunction {
+impl<F> Debug for ListenerFunction<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ListenerFunction")
             .field("id", &self.id)
@@ -123,9 +201,50 @@ pub struct GridEvents {
     listener_id_counter: Arc<Mutex<usize>>,
     /// Collection of registered change event listeners
     changes_listeners: Vec<ListenerFunction>,
+    /// Collection of registered pre-change event listeners
+    pre_changes_listeners: Vec<ListenerFunction<PreChangesEventFn>>,
+    /// Ids of the change listeners that panicked during the last dispatch
+    panicked_listeners: Vec<String>,
+    /// When change listeners are notified
+    dispatch_mode: DispatchMode,
+    /// Change events waiting to be drained, in [`DispatchMode::Queued`]
+    queued_events: VecDeque<ChangesEventValue>,
+    /// Changes waiting to be merged and flushed, in [`DispatchMode::Coalesced`]
+    coalesced_changes: Vec<Change>,
 }
 
 impl GridEvents {
+    /// Generates a new unique listener id.
+    ///
+    /// Ids already taken by named listeners are skipped.
+    fn next_listener_id(&self) -> Result<String, GridEventError> {
+        let mut counter = match self.listener_id_counter.lock() {
+            Ok(counter) => counter,
+            Err(_) => {
+                return Err(GridEventError::ListenerIdNotGenerated);
+            }
+        };
+
+        loop {
+            *counter += 1;
+            let id = format!("l_{}", counter);
+            if !self.has_listener(&id) {
+                return Ok(id);
+            }
+        }
+    }
+
+    /// Poisons the listener id counter, so every new registration fails.
+    #[cfg(test)]
+    pub(crate) fn poison_listener_id_counter(&self) {
+        let counter = self.listener_id_counter.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = counter.lock().unwrap();
+            panic!("poisoning the listener id counter");
+        })
+        .join();
+    }
+
     /// Registers a new change event listener.
     ///
     /// When changes occur in the grid, the provided function will be called
@@ -154,18 +273,58 @@ impl GridEvents {
         &mut self,
         function: impl Fn(&ChangesEventValue) + Send + 'static + Sync,
     ) -> Result<String, GridEventError> {
-        let id = {
-            let mut counter = match self.listener_id_counter.lock() {
-                Ok(counter) => counter,
-                Err(_) => {
-                    return Err(GridEventError::ListenerIdNotGenerated);
-                }
-            };
-            *counter += 1;
-            format!("l_{}", counter)
-        };
+        let id = self.next_listener_id()?;
 
-        let listener = ListenerFunction::new(id.clone(), Box::new(function));
+        let listener = ListenerFunction::new(id.clone(), Box::new(function) as ChangesEventFn);
+
+        self.changes_listeners.push(listener);
+        Ok(id)
+    }
+
+    /// Registers a new change event listener under a chosen id.
+    ///
+    /// Behaves like [`GridEvents::add_changes_listener`], but uses the given name
+    /// as the listener id instead of generating one, which makes listeners easier
+    /// to tell apart when debugging.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The id of the listener, unique among all listeners
+    /// * `function` - The callback function to execute when changes occur
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The listener id, used to remove it later
+    /// * `Err(GridEventError::DuplicateListenerId)` - If a listener already uses the id
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// let id = grid.events_mut().add_named_listener("logger", |event| {
+    ///     println!("Changes occurred: {:?}", event.changes());
+    /// })?;
+    ///
+    /// assert_eq!(id, "logger");
+    /// assert!(grid.events_mut().add_named_listener("logger", |_| {}).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_named_listener(
+        &mut self,
+        name: impl Into<String>,
+        function: impl Fn(&ChangesEventValue) + Send + 'static + Sync,
+    ) -> Result<String, GridEventError> {
+        let id = name.into();
+        if self.has_listener(&id) {
+            return Err(GridEventError::DuplicateListenerId { id });
+        }
+
+        let listener = ListenerFunction::new(id.clone(), Box::new(function) as ChangesEventFn);
 
         self.changes_listeners.push(listener);
         Ok(id)
@@ -207,18 +366,306 @@ impl GridEvents {
         }
     }
 
+    /// Checks whether a listener, of any kind, is registered with the given id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID returned when the listener was registered
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// let listener_id = grid.events_mut().add_changes_listener(|_| {})?;
+    /// assert!(grid.events().has_listener(&listener_id));
+    ///
+    /// grid.events_mut().remove_changes_listener(&listener_id);
+    /// assert!(!grid.events().has_listener(&listener_id));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn has_listener(&self, id: &str) -> bool {
+        self.changes_listeners.iter().any(|l| l.id == id)
+            || self.pre_changes_listeners.iter().any(|l| l.id == id)
+    }
+
+    /// Removes every registered listener, both change and pre-change ones.
+    ///
+    /// Events already queued in [`DispatchMode::Queued`] mode are kept and
+    /// will be drained to whichever listeners are registered by then.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// let listener_id = grid.events_mut().add_changes_listener(|_| {})?;
+    /// grid.events_mut().add_pre_change_listener(|_| false)?;
+    ///
+    /// grid.events_mut().clear_listeners();
+    /// assert!(!grid.events().has_listener(&listener_id));
+    /// grid.add_item("box1", 0, 0, 2, 2)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clear_listeners(&mut self) {
+        self.changes_listeners.clear();
+        self.pre_changes_listeners.clear();
+    }
+
+    /// Registers a new pre-change event listener.
+    ///
+    /// Pre-change listeners run before a batch of changes is applied to the grid.
+    /// They receive the working set of changes computed by the engine, while the
+    /// grid itself is still untouched. Returning `false` from any of them vetoes
+    /// the whole batch, leaving the grid unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The callback function deciding whether the changes may be applied
+    ///
+    /// # Returns
+    ///
+    /// A unique identifier string for the registered listener that can be used
+    /// to remove it later.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::{Change, GridEngine};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    ///
+    /// // Row 0 is reserved
+    /// grid.events_mut().add_pre_change_listener(|event| {
+    ///     event.changes().iter().all(|change| match change {
+    ///         Change::Move(data) => data.new_value().y() > &0,
+    ///         _ => true,
+    ///     })
+    /// })?;
+    ///
+    /// grid.add_item("box1", 0, 2, 2, 2)?;
+    /// assert!(grid.move_item("box1", 0, 0).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_pre_change_listener(
+        &mut self,
+        function: impl Fn(&ChangesEventValue) -> bool + Send + 'static + Sync,
+    ) -> Result<String, GridEventError> {
+        let id = self.next_listener_id()?;
+
+        let listener = ListenerFunction::new(id.clone(), Box::new(function) as PreChangesEventFn);
+
+        self.pre_changes_listeners.push(listener);
+        Ok(id)
+    }
+
+    /// Removes a previously registered pre-change event listener.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID returned when the listener was registered
+    pub fn remove_pre_change_listener(&mut self, id: &str) -> Option<PreChangesEventFn> {
+        if let Some(pos) = self
+            .pre_changes_listeners
+            .iter()
+            .position(|listener| listener.id == id)
+        {
+            let listener = self.pre_changes_listeners.remove(pos);
+            Some(listener.function)
+        } else {
+            None
+        }
+    }
+
+    /// Triggers the pre-change event, asking every listener to accept the changes.
+    ///
+    /// This is called internally by the grid engine before changes are applied.
+    /// Listeners are consulted in registration order and the first veto stops
+    /// the evaluation.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The event data containing the changes about to be applied
+    ///
+    /// # Returns
+    ///
+    /// `true` if every listener accepted the changes, `false` otherwise
+    pub(crate) fn trigger_pre_changes_event(&self, value: &ChangesEventValue) -> bool {
+        self.pre_changes_listeners
+            .iter()
+            .all(|listener| (listener.function)(value))
+    }
+
+    /// Returns the ids of the change listeners that panicked during the last
+    /// dispatch, in registration order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::GridEngine;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// let id = grid.events_mut().add_changes_listener(|_| panic!("broken listener"))?;
+    ///
+    /// grid.add_item("a".to_string(), 0, 0, 1, 1)?;
+    /// assert_eq!(grid.events().panicked_listeners(), [id]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn panicked_listeners(&self) -> &[String] {
+        &self.panicked_listeners
+    }
+
+    /// Returns when change listeners are notified.
+    pub fn dispatch_mode(&self) -> DispatchMode {
+        self.dispatch_mode
+    }
+
+    /// Sets when change listeners are notified.
+    ///
+    /// Events already queued stay queued when switching back to
+    /// [`DispatchMode::Immediate`], until they are drained, and buffered
+    /// changes stay buffered until they are flushed.
+    pub fn set_dispatch_mode(&mut self, mode: DispatchMode) {
+        self.dispatch_mode = mode;
+    }
+
+    /// Notifies the change listeners of every queued event, in order.
+    ///
+    /// Meant for hosts that need listeners to run at a time of their choosing,
+    /// e.g. on a UI thread, along with [`DispatchMode::Queued`].
+    ///
+    /// # Returns
+    ///
+    /// The events that were queued, oldest first
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::{DispatchMode, GridEngine};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.events_mut().set_dispatch_mode(DispatchMode::Queued);
+    ///
+    /// grid.add_item("a", 0, 0, 1, 1)?;
+    /// grid.add_item("b", 1, 0, 1, 1)?;
+    ///
+    /// let events = grid.events_mut().drain_events();
+    /// assert_eq!(events.len(), 2);
+    /// assert!(grid.events_mut().drain_events().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn drain_events(&mut self) -> Vec<ChangesEventValue> {
+        self.panicked_listeners.clear();
+
+        let events: Vec<ChangesEventValue> = self.queued_events.drain(..).collect();
+        for event in &events {
+            self.notify_changes_listeners(event);
+        }
+
+        events
+    }
+
+    /// Notifies the change listeners of the net changes buffered so far.
+    ///
+    /// Meant for [`DispatchMode::Coalesced`], e.g. once a drag ends, so the
+    /// listeners see where each item ended up rather than every step on the way.
+    /// The merged changes are sorted by item id, and nothing is dispatched when
+    /// the items ended up as they started.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(ChangesEventValue)` - The dispatched event
+    /// * `None` - If no net change was buffered
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::grid_engine::{DispatchMode, GridEngine};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut grid = GridEngine::new(10, 10);
+    /// grid.events_mut()
+    ///     .set_dispatch_mode(DispatchMode::Coalesced { threshold: 0 });
+    ///
+    /// grid.add_item("a", 0, 0, 1, 1)?;
+    /// grid.move_item("a", 4, 4)?;
+    ///
+    /// let event = grid.events_mut().flush().unwrap();
+    /// assert_eq!(event.changes().len(), 1);
+    /// assert!(grid.events_mut().flush().is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flush(&mut self) -> Option<ChangesEventValue> {
+        let buffered = ChangesEventValue::new(std::mem::take(&mut self.coalesced_changes));
+        let changes: Vec<Change> = buffered.net_changes().into_values().collect();
+        if changes.is_empty() {
+            return None;
+        }
+
+        let event = ChangesEventValue::new(changes);
+        self.panicked_listeners.clear();
+        self.notify_changes_listeners(&event);
+        Some(event)
+    }
+
     /// Triggers the change event, notifying all registered listeners.
     ///
     /// This is called internally by the grid engine when changes occur.
     /// Each registered listener's callback function is executed with
-    /// the provided change event value.
+    /// the provided change event value. In [`DispatchMode::Queued`], the event
+    /// is queued instead, until [`GridEvents::drain_events`] is called. In
+    /// [`DispatchMode::Coalesced`], the changes are buffered until
+    /// [`GridEvents::flush`] is called or the threshold is reached.
+    ///
+    /// A panicking listener doesn't stop the dispatch, the remaining listeners
+    /// are still notified and the panicked ones are recorded, see
+    /// [`GridEvents::panicked_listeners`].
     ///
     /// # Arguments
     ///
     /// * `value` - The event data containing information about the changes
     pub(crate) fn trigger_changes_event(&mut self, value: &ChangesEventValue) {
-        for listener in &mut self.changes_listeners {
-            (listener.function)(value);
+        match self.dispatch_mode {
+            DispatchMode::Immediate => {
+                self.panicked_listeners.clear();
+                self.notify_changes_listeners(value);
+            }
+            DispatchMode::Queued => self.queued_events.push_back(value.clone()),
+            DispatchMode::Coalesced { threshold } => {
+                self.coalesced_changes.extend(value.changes.iter().cloned());
+                if threshold > 0 && self.coalesced_changes.len() >= threshold {
+                    self.flush();
+                }
+            }
+        }
+    }
+
+    /// Calls every change listener with the event, recording the ones that panic.
+    fn notify_changes_listeners(&mut self, value: &ChangesEventValue) {
+        for listener in &self.changes_listeners {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| (listener.function)(value)));
+            if result.is_err() && !self.panicked_listeners.contains(&listener.id) {
+                self.panicked_listeners.push(listener.id.clone());
+            }
         }
     }
 }
@@ -326,4 +773,183 @@ mod tests {
         let received_change = received.first().unwrap();
         assert_eq!(received_change, &change);
     }
+
+    #[test]
+    fn test_pre_changes_listener_veto() {
+        let mut events = GridEvents::default();
+        let changes = ChangesEventValue { changes: vec![] };
+
+        assert!(events.trigger_pre_changes_event(&changes));
+
+        let accept_id = events.add_pre_change_listener(|_| true).unwrap();
+        assert!(events.trigger_pre_changes_event(&changes));
+
+        let veto_id = events.add_pre_change_listener(|_| false).unwrap();
+        assert_ne!(accept_id, veto_id);
+        assert!(!events.trigger_pre_changes_event(&changes));
+
+        assert!(events.remove_pre_change_listener(&veto_id).is_some());
+        assert!(events.trigger_pre_changes_event(&changes));
+    }
+
+    #[test]
+    fn test_net_changes() {
+        let origin = Node::new("a".to_string(), 0, 0, 2, 2);
+        let middle = Node::new("a".to_string(), 0, 2, 2, 2);
+        let last = Node::new("a".to_string(), 0, 4, 2, 2);
+        let added = Node::new("b".to_string(), 4, 0, 1, 1);
+        let event = ChangesEventValue::new(vec![
+            Change::Move(MoveChangeData::new(origin.clone(), middle.clone())),
+            Change::Add(AddChangeData::new(added.clone())),
+            Change::Move(MoveChangeData::new(middle, last.clone())),
+            Change::Remove(RemoveChangeData::new(added)),
+        ]);
+
+        let net = event.net_changes();
+        assert_eq!(net.len(), 1);
+        assert_eq!(
+            net.get("a"),
+            Some(&Change::Move(MoveChangeData::new(origin, last)))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_changes_event_value_serde_round_trip() {
+        let node = Node::new("a".to_string(), 0, 0, 1, 1);
+        let event = ChangesEventValue::new(vec![
+            Change::Add(AddChangeData::new(node.clone())),
+            Change::Move(MoveChangeData::new(node.clone(), node.with_position(1, 1))),
+        ]);
+
+        let json = serde_json::to_string(&event).unwrap();
+        let parsed: ChangesEventValue = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, event);
+    }
+
+    #[test]
+    fn test_panicking_listener_does_not_stop_dispatch() {
+        let mut events = GridEvents::default();
+        let called = Arc::new(Mutex::new(false));
+        let called_clone = called.clone();
+
+        let panicking_id = events
+            .add_changes_listener(|_| panic!("listener failure"))
+            .unwrap();
+        events
+            .add_changes_listener(move |_| *called_clone.lock().unwrap() = true)
+            .unwrap();
+
+        events.trigger_changes_event(&ChangesEventValue::new(vec![]));
+
+        assert!(*called.lock().unwrap());
+        assert_eq!(events.panicked_listeners(), [panicking_id]);
+    }
+
+    #[test]
+    fn test_add_named_listener() {
+        let mut events = GridEvents::default();
+
+        assert_eq!(events.add_named_listener("first", |_| {}).unwrap(), "first");
+        assert_eq!(
+            events.add_named_listener("second", |_| {}).unwrap(),
+            "second"
+        );
+        assert!(matches!(
+            events.add_named_listener("first", |_| {}),
+            Err(GridEventError::DuplicateListenerId { ref id }) if id == "first"
+        ));
+        assert_eq!(events.changes_listeners.len(), 2);
+    }
+
+    #[test]
+    fn test_generated_listener_id_skips_named_ids() {
+        let mut events = GridEvents::default();
+        events.add_named_listener("l_1", |_| {}).unwrap();
+
+        let id = events.add_changes_listener(|_| {}).unwrap();
+
+        assert_eq!(id, "l_2");
+    }
+
+    #[test]
+    fn test_queued_dispatch_waits_for_drain() {
+        let mut events = GridEvents::default();
+        let calls = Arc::new(Mutex::new(0));
+        let calls_clone = calls.clone();
+        events
+            .add_changes_listener(move |_| *calls_clone.lock().unwrap() += 1)
+            .unwrap();
+
+        events.set_dispatch_mode(DispatchMode::Queued);
+        events.trigger_changes_event(&ChangesEventValue::new(vec![]));
+        events.trigger_changes_event(&ChangesEventValue::new(vec![]));
+        assert_eq!(*calls.lock().unwrap(), 0);
+
+        assert_eq!(events.drain_events().len(), 2);
+        assert_eq!(*calls.lock().unwrap(), 2);
+        assert!(events.drain_events().is_empty());
+
+        events.set_dispatch_mode(DispatchMode::Immediate);
+        events.trigger_changes_event(&ChangesEventValue::new(vec![]));
+        assert_eq!(*calls.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_clear_listeners() {
+        let mut events = GridEvents::default();
+        let changes_id = events.add_changes_listener(|_| {}).unwrap();
+        let pre_changes_id = events.add_pre_change_listener(|_| true).unwrap();
+        assert!(events.has_listener(&changes_id));
+        assert!(events.has_listener(&pre_changes_id));
+        assert!(!events.has_listener("missing"));
+
+        events.clear_listeners();
+
+        assert!(events.changes_listeners.is_empty());
+        assert!(events.pre_changes_listeners.is_empty());
+        assert!(!events.has_listener(&changes_id));
+        assert!(!events.has_listener(&pre_changes_id));
+    }
+
+    #[test]
+    fn test_coalesced_dispatch_merges_changes() {
+        let received: Arc<Mutex<Vec<ChangesEventValue>>> = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        let mut events = GridEvents::default();
+        events
+            .add_changes_listener(move |event| received_clone.lock().unwrap().push(event.clone()))
+            .unwrap();
+        events.set_dispatch_mode(DispatchMode::Coalesced { threshold: 0 });
+
+        let step = |from: (usize, usize), to: (usize, usize)| {
+            Change::Move(MoveChangeData::new(
+                Node::new("a", from.0, from.1, 1, 1),
+                Node::new("a", to.0, to.1, 1, 1),
+            ))
+        };
+        events.trigger_changes_event(&ChangesEventValue::new(vec![step((0, 0), (1, 0))]));
+        events.trigger_changes_event(&ChangesEventValue::new(vec![step((1, 0), (2, 0))]));
+        events.trigger_changes_event(&ChangesEventValue::new(vec![step((2, 0), (2, 1))]));
+        assert!(received.lock().unwrap().is_empty());
+
+        let expected = ChangesEventValue::new(vec![step((0, 0), (2, 1))]);
+        assert_eq!(events.flush(), Some(expected.clone()));
+        assert_eq!(*received.lock().unwrap(), vec![expected]);
+        assert_eq!(events.flush(), None);
+
+        // Reaching the threshold flushes on its own
+        events.set_dispatch_mode(DispatchMode::Coalesced { threshold: 2 });
+        events.trigger_changes_event(&ChangesEventValue::new(vec![step((2, 1), (3, 1))]));
+        assert_eq!(received.lock().unwrap().len(), 1);
+        events.trigger_changes_event(&ChangesEventValue::new(vec![step((3, 1), (2, 1))]));
+        assert_eq!(received.lock().unwrap().len(), 1);
+        events.trigger_changes_event(&ChangesEventValue::new(vec![step((2, 1), (4, 4))]));
+        events.trigger_changes_event(&ChangesEventValue::new(vec![step((4, 4), (5, 5))]));
+        assert_eq!(received.lock().unwrap().len(), 2);
+        assert_eq!(
+            received.lock().unwrap()[1],
+            ChangesEventValue::new(vec![step((2, 1), (5, 5))])
+        );
+    }
 }
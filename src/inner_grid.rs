@@ -27,10 +27,33 @@
 //!
 //! The grid automatically expands vertically when needed, allowing for
 //! flexible layout management while maintaining horizontal constraints.
+//!
+//! Cells hold small [`NodeHandle`]s rather than the items' ids, so placing and
+//! clearing cells doesn't allocate. Each grid keeps the ids its handles stand
+//! for, as long as some cell holds them.
 
-use crate::{error::InnerGridError, node::Node};
+use crate::{error::InnerGridError, geometry::Axis, node::Node};
 use grid::Grid;
-use std::ops::{Deref, DerefMut};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Compact reference to an item id, stored in the grid cells.
+///
+/// Handles are only meaningful for the [`InnerGrid`] that issued them, use
+/// [`InnerGrid::resolve`] to get the id back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeHandle(u32);
+
+/// An id placed on the grid, along with the number of cells holding its handle.
+#[derive(Debug, Clone)]
+struct HandleEntry {
+    /// The id the handle stands for
+    id: Arc<str>,
+    /// Number of cells holding the handle
+    cells: usize,
+}
 
 /// Operation to perform when updating the grid.
 #[derive(Debug, Clone, Copy)]
@@ -41,46 +64,173 @@ pub enum UpdateGridOperation {
     Remove,
 }
 
+/// How many rows the grid adds when it expands vertically, see
+/// [`GridEngine::set_auto_grow_policy`](crate::grid_engine::GridEngine::set_auto_grow_policy)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AutoGrowPolicy {
+    /// Add exactly the rows needed to reach the accessed row
+    #[default]
+    Exact,
+    /// Add the rows needed rounded up to a multiple of the given chunk size,
+    /// a chunk size of 0 behaves like [`AutoGrowPolicy::Exact`]
+    Chunked(usize),
+    /// Double the number of rows, or add the rows needed if that's more
+    Doubling,
+}
+
 /// Internal grid structure that manages the spatial layout of nodes.
 ///
 /// The grid maintains a 2D layout of cells, where each cell can either be
-/// empty (None) or contain a node ID (Some(String)). The grid can dynamically
-/// expand vertically to accommodate new nodes.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+/// empty (None) or contain the handle of a node ID (Some(NodeHandle)). The
+/// grid can dynamically expand vertically to accommodate new nodes.
+///
+/// A handle is issued when an id gets its first cell and released once its
+/// last cell is cleared, to be reused by the next new id, so the handle table
+/// only holds the ids currently on the grid. Ids are shared, cloning the grid
+/// never copies them. Equality, hashing and the serialized form only look at
+/// the ids the cells resolve to, never at the handles themselves.
+///
+/// Methods take coordinates following the crate convention, `x` being the
+/// column and `y` the row, see [`crate::geometry`]. The underlying [`Grid`]
+/// is indexed the other way around, as (row, col), which is only dealt with here.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "InnerGridRepr", try_from = "InnerGridRepr")
+)]
 pub struct InnerGrid {
     /// Whether the grid can expand vertically (add rows)
     can_expand_y: bool,
+    /// Whether the grid can expand horizontally (add columns)
+    can_expand_x: bool,
+    /// Maximum number of rows the grid can expand to, unbounded when `None`
+    max_rows: Option<usize>,
+    /// How many rows are added when expanding vertically
+    grow_policy: AutoGrowPolicy,
+    /// The underlying grid structure
+    inner: Grid<Option<NodeHandle>>,
+    /// The id each handle stands for, indexed by handle, `None` once released
+    ids: Vec<Option<HandleEntry>>,
+    /// Released handles, waiting to be reused
+    free: Vec<NodeHandle>,
+    /// The handle issued for each id on the grid
+    handles: HashMap<Arc<str>, NodeHandle>,
+}
+
+impl PartialEq for InnerGrid {
+    fn eq(&self, other: &Self) -> bool {
+        self.can_expand_y == other.can_expand_y
+            && self.can_expand_x == other.can_expand_x
+            && self.max_rows == other.max_rows
+            && self.grow_policy == other.grow_policy
+            && self.rows() == other.rows()
+            && self.cols() == other.cols()
+            && self.iter_cells().eq(other.iter_cells())
+    }
+}
+
+impl Eq for InnerGrid {}
+
+impl Hash for InnerGrid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.can_expand_y.hash(state);
+        self.can_expand_x.hash(state);
+        self.max_rows.hash(state);
+        self.grow_policy.hash(state);
+        self.rows().hash(state);
+        self.cols().hash(state);
+        for (_, _, cell) in self.iter_cells() {
+            cell.hash(state);
+        }
+    }
+}
+
+/// Serialized form of an [`InnerGrid`], with the cells holding ids.
+#[cfg(feature = "serde")]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct InnerGridRepr {
+    /// Whether the grid can expand vertically (add rows)
+    #[serde(default = "default_can_expand_y")]
+    can_expand_y: bool,
+    /// Whether the grid can expand horizontally (add columns)
+    #[serde(default)]
+    can_expand_x: bool,
+    /// Maximum number of rows the grid can expand to, unbounded when `None`
+    #[serde(default)]
+    max_rows: Option<usize>,
+    /// How many rows are added when expanding vertically
+    #[serde(default)]
+    grow_policy: AutoGrowPolicy,
     /// The underlying grid structure
     inner: Grid<Option<String>>,
 }
 
+/// Vertical expansion is enabled unless the serialized grid says otherwise.
+#[cfg(feature = "serde")]
+fn default_can_expand_y() -> bool {
+    true
+}
+
+#[cfg(feature = "serde")]
+impl From<InnerGrid> for InnerGridRepr {
+    fn from(grid: InnerGrid) -> Self {
+        let mut inner = Grid::new(grid.rows(), grid.cols());
+        for (x, y, cell) in grid.iter_cells() {
+            inner[(y, x)] = cell.map(str::to_string);
+        }
+
+        InnerGridRepr {
+            can_expand_y: grid.can_expand_y,
+            can_expand_x: grid.can_expand_x,
+            max_rows: grid.max_rows,
+            grow_policy: grid.grow_policy,
+            inner,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<InnerGridRepr> for InnerGrid {
+    type Error = InnerGridError;
+
+    fn try_from(repr: InnerGridRepr) -> Result<Self, Self::Error> {
+        let mut grid = InnerGrid {
+            can_expand_y: repr.can_expand_y,
+            can_expand_x: repr.can_expand_x,
+            max_rows: repr.max_rows,
+            grow_policy: repr.grow_policy,
+            inner: Grid::new(repr.inner.rows(), repr.inner.cols()),
+            ids: Vec::new(),
+            free: Vec::new(),
+            handles: HashMap::new(),
+        };
+        for ((y, x), cell) in repr.inner.indexed_iter() {
+            grid.set(x, y, cell.as_deref())?;
+        }
+
+        Ok(grid)
+    }
+}
+
 /// Allows using InnerGrid with methods from the underlying Grid type.
 ///
 /// This implementation enables transparent access to Grid methods without
 /// explicitly accessing the inner field.
 impl Deref for InnerGrid {
-    type Target = Grid<Option<String>>;
+    type Target = Grid<Option<NodeHandle>>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
     }
 }
 
-/// Allows mutable access to the underlying Grid methods.
-///
-/// This implementation enables modifying the grid using Grid methods
-/// while maintaining InnerGrid's invariants.
-impl DerefMut for InnerGrid {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
-    }
-}
-
 impl InnerGrid {
     /// Creates a new grid with the specified dimensions.
     ///
     /// The grid is initially empty (all cells are None) and can expand
-    /// vertically by default.
+    /// vertically by default, while the number of columns is fixed.
     ///
     /// # Arguments
     ///
@@ -91,30 +241,194 @@ impl InnerGrid {
         InnerGrid {
             inner,
             can_expand_y: true,
+            can_expand_x: false,
+            max_rows: None,
+            grow_policy: AutoGrowPolicy::Exact,
+            ids: Vec::new(),
+            free: Vec::new(),
+            handles: HashMap::new(),
         }
     }
 
+    /// Returns the handle of an id for one more cell, issuing one if needed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(NodeHandle)` - The handle to store in the cell
+    /// * `Err(InnerGridError::HandlesExhausted)` - If every handle is taken
+    fn acquire(&mut self, id: &str) -> Result<NodeHandle, InnerGridError> {
+        if let Some(&handle) = self.handles.get(id) {
+            if let Some(entry) = &mut self.ids[handle.0 as usize] {
+                entry.cells += 1;
+            }
+            return Ok(handle);
+        }
+
+        let entry = HandleEntry {
+            id: Arc::from(id),
+            cells: 1,
+        };
+        let handle = match self.free.pop() {
+            Some(handle) => handle,
+            None => {
+                let index = u32::try_from(self.ids.len())
+                    .map_err(|_| InnerGridError::HandlesExhausted { id: id.to_string() })?;
+                self.ids.push(None);
+                NodeHandle(index)
+            }
+        };
+        self.handles.insert(entry.id.clone(), handle);
+        self.ids[handle.0 as usize] = Some(entry);
+        Ok(handle)
+    }
+
+    /// Gives back the handle of a cleared cell, releasing it with its last cell.
+    fn release(&mut self, handle: NodeHandle) {
+        let slot = &mut self.ids[handle.0 as usize];
+        let Some(entry) = slot else {
+            return;
+        };
+
+        entry.cells -= 1;
+        if entry.cells == 0 {
+            self.handles.remove(&entry.id);
+            *slot = None;
+            self.free.push(handle);
+        }
+    }
+
+    /// Returns the id a handle stands for.
+    ///
+    /// # Panics
+    ///
+    /// If the handle wasn't issued by this grid, or a clone of it, or was
+    /// released since
+    pub fn resolve(&self, handle: NodeHandle) -> &str {
+        match &self.ids[handle.0 as usize] {
+            Some(entry) => &entry.id,
+            None => panic!("node handle {} was released", handle.0),
+        }
+    }
+
+    /// Returns the handle issued for an id, if some cell currently holds it.
+    pub fn handle(&self, id: &str) -> Option<NodeHandle> {
+        self.handles.get(id).copied()
+    }
+
+    /// Returns whether the grid can expand vertically.
+    pub fn can_expand_y(&self) -> bool {
+        self.can_expand_y
+    }
+
+    /// Sets whether the grid can expand vertically.
+    pub fn set_can_expand_y(&mut self, can_expand_y: bool) {
+        self.can_expand_y = can_expand_y;
+    }
+
+    /// Returns whether the grid can expand horizontally.
+    pub fn can_expand_x(&self) -> bool {
+        self.can_expand_x
+    }
+
+    /// Sets whether the grid can expand horizontally.
+    pub fn set_can_expand_x(&mut self, can_expand_x: bool) {
+        self.can_expand_x = can_expand_x;
+    }
+
+    /// Returns the maximum number of rows the grid can expand to.
+    pub fn max_rows(&self) -> Option<usize> {
+        self.max_rows
+    }
+
+    /// Sets the maximum number of rows the grid can expand to.
+    ///
+    /// Existing rows beyond the limit are kept, the limit only stops further expansion.
+    pub fn set_max_rows(&mut self, max_rows: Option<usize>) {
+        self.max_rows = max_rows;
+    }
+
+    /// Returns how many rows are added when expanding vertically.
+    pub fn auto_grow_policy(&self) -> AutoGrowPolicy {
+        self.grow_policy
+    }
+
+    /// Sets how many rows are added when expanding vertically.
+    pub fn set_auto_grow_policy(&mut self, policy: AutoGrowPolicy) {
+        self.grow_policy = policy;
+    }
+
     /// Handles automatic grid expansion when accessing cells.
     ///
-    /// If the requested y-coordinate is beyond the current grid bounds
-    /// and expansion is allowed, the grid will automatically add rows
-    /// to accommodate the access.
+    /// If the requested coordinates are beyond the current grid bounds
+    /// and expansion is allowed on that axis, the grid will automatically
+    /// add rows or columns to accommodate the access. Rows are added according
+    /// to the [`AutoGrowPolicy`], without going past the row limit.
     ///
     /// # Arguments
     ///
     /// * `x` - X coordinate to check
     /// * `y` - Y coordinate to check
     fn handle_expansion(&mut self, x: usize, y: usize) {
-        let rows = self.rows();
         let cols = self.cols();
 
-        let can_expand = self.can_expand_y && x < cols;
+        if self.can_expand_x && x >= cols {
+            self.inner.expand_cols(x - cols + 1);
+        }
+
+        let rows = self.rows();
+        let can_expand = self.can_expand_y
+            && x < self.cols()
+            && self.max_rows.is_none_or(|max_rows| y < max_rows);
 
         if can_expand && y >= rows {
-            self.expand_rows(y - rows + 1);
+            let needed = y - rows + 1;
+            let added = match self.grow_policy {
+                AutoGrowPolicy::Exact | AutoGrowPolicy::Chunked(0) => needed,
+                AutoGrowPolicy::Chunked(chunk) => needed.div_ceil(chunk) * chunk,
+                AutoGrowPolicy::Doubling => needed.max(rows),
+            };
+            let added = match self.max_rows {
+                Some(max_rows) => added.min(max_rows - rows),
+                None => added,
+            };
+            self.inner.expand_rows(added);
         }
     }
 
+    /// Checks whether a cell is within bounds, or would be once the grid
+    /// expands to reach it.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the cell
+    /// * `y` - Y coordinate of the cell
+    pub fn can_reach(&self, x: usize, y: usize) -> bool {
+        let reaches_x = x < self.cols() || self.can_expand_x;
+        let reaches_y = y < self.rows()
+            || (self.can_expand_y && self.max_rows.is_none_or(|max_rows| y < max_rows));
+
+        reaches_x && reaches_y
+    }
+
+    /// Builds the error for a cell that is out of bounds and can't be reached.
+    ///
+    /// The x axis is blamed when the cell lies past columns the grid can't
+    /// grow into, the y axis otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the cell
+    /// * `y` - Y coordinate of the cell
+    pub fn out_of_bounds(&self, x: usize, y: usize) -> InnerGridError {
+        let axis = if x >= self.cols() && !self.can_expand_x {
+            Axis::X
+        } else {
+            Axis::Y
+        };
+
+        InnerGridError::OutOfBoundsAccess { x, y, axis }
+    }
+
     /// Gets a reference to the cell at the specified coordinates.
     ///
     /// If the coordinates are beyond the current grid bounds and expansion
@@ -127,28 +441,188 @@ impl InnerGrid {
     ///
     /// # Returns
     ///
-    /// * `Some(&Option<String>)` - Reference to the cell if coordinates are valid
+    /// * `Some(Option<&str>)` - The id occupying the cell, if any, when coordinates are valid
     /// * `None` - If coordinates are invalid or beyond expansion limits
-    pub fn get(&mut self, x: usize, y: usize) -> Option<&Option<String>> {
+    pub fn get(&mut self, x: usize, y: usize) -> Option<Option<&str>> {
+        // The underlying grid is indexed as (row, col)
         if self.inner.get(y, x).is_none() {
             self.handle_expansion(x, y);
         }
 
-        self.inner.get(y, x)
+        self.peek(x, y)
     }
 
-    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Option<String>> {
+    /// Gets a reference to the cell at the specified coordinates without expanding.
+    ///
+    /// Unlike [`InnerGrid::get`], this never grows the grid, so it can be used
+    /// for read-only queries.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the cell
+    /// * `y` - Y coordinate of the cell
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Option<&str>)` - The id occupying the cell, if any, when within the current bounds
+    /// * `None` - If coordinates are outside the current bounds
+    pub fn peek(&self, x: usize, y: usize) -> Option<Option<&str>> {
+        self.inner
+            .get(y, x)
+            .map(|cell| cell.map(|handle| self.resolve(handle)))
+    }
+
+    /// Gets the id occupying the cell at the specified coordinates without expanding.
+    ///
+    /// Unlike [`InnerGrid::peek`], an out of bounds access is reported as an
+    /// error, so it can't be mistaken for an empty cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the cell
+    /// * `y` - Y coordinate of the cell
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(&str))` - The id of the item occupying the cell
+    /// * `Ok(None)` - If the cell is empty
+    /// * `Err(InnerGridError::OutOfBoundsAccess)` - If coordinates are outside the current bounds
+    pub fn get_checked(&self, x: usize, y: usize) -> Result<Option<&str>, InnerGridError> {
+        self.peek(x, y).ok_or_else(|| self.out_of_bounds(x, y))
+    }
+
+    /// Iterates over every cell with its coordinates, in row-major order.
+    ///
+    /// Coordinates follow the x/y convention used by the rest of the crate,
+    /// even though the underlying grid is indexed as (row, col).
+    ///
+    /// # Returns
+    ///
+    /// An iterator of `(x, y, cell)`, where `cell` is the id of the item
+    /// occupying the cell, if any
+    pub fn iter_cells(&self) -> impl Iterator<Item = (usize, usize, Option<&str>)> {
+        self.inner
+            .indexed_iter()
+            .map(|((y, x), cell)| (x, y, cell.map(|handle| self.resolve(handle))))
+    }
+
+    /// Iterates over the ids occupying the cells of a row, left to right.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - Index of the row
+    ///
+    /// # Panics
+    ///
+    /// If the row is out of bounds, like [`Grid::iter_row`]
+    pub fn iter_row_ids(&self, y: usize) -> impl Iterator<Item = Option<&str>> {
+        self.inner
+            .iter_row(y)
+            .map(|cell| cell.map(|handle| self.resolve(handle)))
+    }
+
+    /// Sets the id occupying the cell at the specified coordinates.
+    ///
+    /// Like [`InnerGrid::get`], the grid expands to reach the cell when allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the cell
+    /// * `y` - Y coordinate of the cell
+    /// * `id` - The id to store, `None` to clear the cell
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cell was set
+    /// * `Err(InnerGridError::OutOfBoundsAccess)` - If the cell can't be reached
+    pub fn set(&mut self, x: usize, y: usize, id: Option<&str>) -> Result<(), InnerGridError> {
         if self.inner.get(y, x).is_none() {
             self.handle_expansion(x, y);
         }
 
-        self.inner.get_mut(y, x)
+        let Some(&previous) = self.inner.get(y, x) else {
+            return Err(self.out_of_bounds(x, y));
+        };
+        let handle = id.map(|id| self.acquire(id)).transpose()?;
+        self.inner[(y, x)] = handle;
+        if let Some(previous) = previous {
+            self.release(previous);
+        }
+        Ok(())
+    }
+
+    /// Checks whether no cell of a row is occupied.
+    ///
+    /// Rows past the current bounds hold no cells, so they count as empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - Index of the row
+    pub fn row_is_empty(&self, y: usize) -> bool {
+        y >= self.rows() || self.iter_row(y).all(Option::is_none)
+    }
+
+    /// Returns the indices of the rows where no cell is occupied, in order.
+    pub fn empty_rows(&self) -> Vec<usize> {
+        (0..self.rows()).filter(|&y| self.row_is_empty(y)).collect()
+    }
+
+    /// Appends `rows` empty rows at the bottom of the grid.
+    ///
+    /// Ignores the expansion settings, callers are expected to check them.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - Number of rows to add
+    pub fn expand_rows(&mut self, rows: usize) {
+        self.inner.expand_rows(rows);
+    }
+
+    /// Removes trailing rows until the grid has at most `rows` rows.
+    ///
+    /// At least one row is always kept, since an empty grid would lose its
+    /// column count and could no longer expand.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - Target number of rows
+    pub fn truncate_rows(&mut self, rows: usize) {
+        let target = rows.max(1);
+
+        while self.rows() > target {
+            for handle in self.inner.pop_row().into_iter().flatten().flatten() {
+                self.release(handle);
+            }
+        }
+    }
+
+    /// Adds or removes trailing columns until the grid has exactly `cols` columns.
+    ///
+    /// Cells in removed columns are dropped, so callers must move anything
+    /// living there first. At least one column is always kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `cols` - Target number of columns
+    pub fn resize_cols(&mut self, cols: usize) {
+        let target = cols.max(1);
+
+        while self.cols() > target {
+            for handle in self.inner.pop_col().into_iter().flatten().flatten() {
+                self.release(handle);
+            }
+        }
+        let cols = self.cols();
+        if cols < target {
+            self.inner.expand_cols(target - cols);
+        }
     }
 
     /// Updates a cell in the grid based on the specified operation.
     ///
     /// Adds or removes a node's ID from the specified cell. When removing,
-    /// it only clears the cell if it contains the specified node's ID.
+    /// it only clears the cell if it contains the specified node's ID, and
+    /// never expands the grid: cells past the bounds hold nothing to remove.
     ///
     /// # Arguments
     ///
@@ -160,7 +634,7 @@ impl InnerGrid {
     /// # Returns
     ///
     /// * `Ok(())` - If the update was successful
-    /// * `Err(InnerGridError)` - If the coordinates are invalid
+    /// * `Err(InnerGridError)` - If the coordinates are invalid for an addition
     pub fn update(
         &mut self,
         node: &Node,
@@ -168,29 +642,28 @@ impl InnerGrid {
         y: usize,
         operation: UpdateGridOperation,
     ) -> Result<(), InnerGridError> {
-        let cell = self
-            .get_mut(x, y)
-            .ok_or(InnerGridError::OutOfBoundsAccess { x, y })?;
-
         match operation {
-            UpdateGridOperation::Add => {
-                *cell = Some(node.id().to_string());
-            }
+            UpdateGridOperation::Add => self.set(x, y, Some(node.id())),
             UpdateGridOperation::Remove => {
-                if cell.as_ref() == Some(&node.id().to_string()) {
-                    *cell = None;
+                if let Some(&Some(handle)) = self.inner.get(y, x)
+                    && self.handle(node.id()) == Some(handle)
+                {
+                    self.inner[(y, x)] = None;
+                    self.release(handle);
                 }
+                Ok(())
             }
         }
-        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::error::InnerGridError;
-    use crate::inner_grid::{InnerGrid, UpdateGridOperation};
+    use crate::geometry::Axis;
+    use crate::inner_grid::{AutoGrowPolicy, InnerGrid, NodeHandle, UpdateGridOperation};
     use crate::node::Node;
+    use std::collections::HashMap;
 
     #[test]
     fn test_update_grid_add_node() {
@@ -202,10 +675,12 @@ mod tests {
             h: 1,
             x: 1,
             y: 1,
+            weight: 1,
+            seq: 0,
         };
 
         grid.update(&node, 1, 1, UpdateGridOperation::Add).unwrap();
-        assert_eq!(grid.get(1, 1), Some(&Some("test_node".to_string())));
+        assert_eq!(grid.get(1, 1), Some(Some("test_node")));
     }
 
     #[test]
@@ -218,17 +693,17 @@ mod tests {
             h: 1,
             x: 1,
             y: 1,
+            weight: 1,
+            seq: 0,
         };
 
         // First add the node
-        if let Some(cell) = grid.get_mut(1, 1) {
-            *cell = Some("test_node".to_string());
-        }
+        grid.set(1, 1, Some("test_node")).unwrap();
 
         // Then remove it
         grid.update(&node, 1, 1, UpdateGridOperation::Remove)
             .unwrap();
-        assert_eq!(grid.get(1, 1), Some(&None));
+        assert_eq!(grid.get(1, 1), Some(None));
     }
 
     #[test]
@@ -241,19 +716,19 @@ mod tests {
             h: 1,
             x: 1,
             y: 1,
+            weight: 1,
+            seq: 0,
         };
 
         // Add a different node's ID
-        if let Some(cell) = grid.get_mut(1, 1) {
-            *cell = Some("different_node".to_string());
-        }
+        grid.set(1, 1, Some("different_node")).unwrap();
 
         // Try to remove our node
         grid.update(&node, 1, 1, UpdateGridOperation::Remove)
             .unwrap();
 
         // The different node should still be there
-        assert_eq!(grid.get(1, 1), Some(&Some("different_node".to_string())));
+        assert_eq!(grid.get(1, 1), Some(Some("different_node")));
     }
 
     #[test]
@@ -265,12 +740,18 @@ mod tests {
             h: 1,
             x: 0,
             y: 0,
+            weight: 1,
+            seq: 0,
         };
 
         let result = grid.update(&node, 3, 3, UpdateGridOperation::Add);
         assert!(matches!(
             result,
-            Err(InnerGridError::OutOfBoundsAccess { x: 3, y: 3 })
+            Err(InnerGridError::OutOfBoundsAccess {
+                x: 3,
+                y: 3,
+                axis: Axis::X
+            })
         ));
     }
 
@@ -283,6 +764,8 @@ mod tests {
             h: 1,
             x: 1,
             y: 4,
+            weight: 1,
+            seq: 0,
         };
 
         // Try to add node at y=4 (beyond current grid size) with can_expand_y=true
@@ -291,7 +774,7 @@ mod tests {
 
         // Verify grid has expanded and node was added
         assert_eq!(grid.rows(), 5); // Grid should have expanded to 5 rows
-        assert_eq!(grid.get(1, 4), Some(&Some("test_node".to_string())));
+        assert_eq!(grid.get(1, 4), Some(Some("test_node")));
     }
 
     #[test]
@@ -304,6 +787,8 @@ mod tests {
             h: 1,
             x: 1,
             y: 4,
+            weight: 1,
+            seq: 0,
         };
 
         // Try to add node at y=4 (beyond current grid size) with can_expand_y=false
@@ -312,10 +797,304 @@ mod tests {
         // Verify operation failed with OutOfBoundsAccess
         assert!(matches!(
             result,
-            Err(InnerGridError::OutOfBoundsAccess { x: 1, y: 4 })
+            Err(InnerGridError::OutOfBoundsAccess {
+                x: 1,
+                y: 4,
+                axis: Axis::Y
+            })
         ));
 
         // Verify grid size hasn't changed
         assert_eq!(grid.rows(), 3);
     }
+
+    #[test]
+    fn test_truncate_rows() {
+        let mut grid = InnerGrid::new(5, 3);
+
+        grid.truncate_rows(2);
+        assert_eq!(grid.rows(), 2);
+        assert_eq!(grid.cols(), 3);
+
+        // Never drops the last row
+        grid.truncate_rows(0);
+        assert_eq!(grid.rows(), 1);
+        assert_eq!(grid.cols(), 3);
+    }
+
+    #[test]
+    fn test_empty_rows() {
+        let mut grid = InnerGrid::new(7, 3);
+        let node = Node::new("0", 0, 0, 1, 1);
+        grid.update(&node, 1, 0, UpdateGridOperation::Add).unwrap();
+        grid.update(&node, 2, 5, UpdateGridOperation::Add).unwrap();
+
+        assert_eq!(grid.empty_rows(), vec![1, 2, 3, 4, 6]);
+        assert!(!grid.row_is_empty(5));
+        assert!(grid.row_is_empty(3));
+        assert!(grid.row_is_empty(10));
+    }
+
+    #[test]
+    fn test_resize_cols() {
+        let mut grid = InnerGrid::new(2, 4);
+
+        grid.resize_cols(6);
+        assert_eq!((grid.rows(), grid.cols()), (2, 6));
+
+        grid.resize_cols(2);
+        assert_eq!((grid.rows(), grid.cols()), (2, 2));
+
+        // Never drops the last column
+        grid.resize_cols(0);
+        assert_eq!((grid.rows(), grid.cols()), (2, 1));
+    }
+
+    #[test]
+    fn test_grid_expands_when_can_expand_x_is_true() {
+        let mut grid = InnerGrid::new(3, 3);
+        grid.set_can_expand_x(true);
+        let node = Node {
+            id: String::from("test_node"),
+            w: 1,
+            h: 1,
+            x: 4,
+            y: 1,
+            weight: 1,
+            seq: 0,
+        };
+
+        let result = grid.update(&node, 4, 1, UpdateGridOperation::Add);
+        assert!(result.is_ok());
+
+        assert_eq!(grid.cols(), 5);
+        assert_eq!(grid.rows(), 3);
+        assert_eq!(grid.get(4, 1), Some(Some("test_node")));
+    }
+
+    #[test]
+    fn test_grid_does_not_expand_x_by_default() {
+        let mut grid = InnerGrid::new(3, 3);
+        assert!(!grid.can_expand_x());
+        assert!(grid.get(3, 0).is_none());
+        assert_eq!(grid.cols(), 3);
+    }
+
+    #[test]
+    fn test_iter_cells() {
+        let mut grid = InnerGrid::new(3, 4);
+        let node = Node::new("test_node", 2, 1, 2, 1);
+        node.update_grid(&mut grid, UpdateGridOperation::Add)
+            .unwrap();
+
+        let occupied: Vec<(usize, usize)> = grid
+            .iter_cells()
+            .filter(|(_, _, cell)| *cell == Some("test_node"))
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert_eq!(occupied, vec![(2, 1), (3, 1)]);
+        assert_eq!(grid.iter_cells().count(), 12);
+        assert_eq!(grid.iter_cells().nth(5), Some((1, 1, None)));
+    }
+
+    #[test]
+    fn test_grid_does_not_expand_past_max_rows() {
+        let mut grid = InnerGrid::new(3, 3);
+        grid.set_max_rows(Some(5));
+
+        assert!(grid.get(0, 4).is_some());
+        assert_eq!(grid.rows(), 5);
+
+        assert!(grid.get(0, 5).is_none());
+        assert_eq!(grid.rows(), 5);
+    }
+
+    #[test]
+    fn test_get_checked() {
+        let mut grid = InnerGrid::new(2, 2);
+        let node = Node::new("a".to_string(), 1, 0, 1, 1);
+        grid.update(&node, 1, 0, UpdateGridOperation::Add).unwrap();
+
+        assert_eq!(grid.get_checked(0, 0).unwrap(), None);
+        assert_eq!(grid.get_checked(1, 0).unwrap(), Some("a"));
+        assert!(matches!(
+            grid.get_checked(2, 5),
+            Err(InnerGridError::OutOfBoundsAccess {
+                x: 2,
+                y: 5,
+                axis: Axis::X
+            })
+        ));
+        assert_eq!(grid.rows(), 2);
+    }
+
+    #[test]
+    fn test_out_of_bounds_reports_axis() {
+        let mut grid = InnerGrid::new(2, 3);
+        grid.set_max_rows(Some(4));
+        let node = Node::new("a", 0, 0, 1, 1);
+
+        assert!(matches!(
+            grid.update(&node, 3, 0, UpdateGridOperation::Add),
+            Err(InnerGridError::OutOfBoundsAccess { axis: Axis::X, .. })
+        ));
+        assert!(matches!(
+            grid.update(&node, 0, 4, UpdateGridOperation::Add),
+            Err(InnerGridError::OutOfBoundsAccess { axis: Axis::Y, .. })
+        ));
+        assert_eq!(
+            grid.out_of_bounds(5, 1).to_string(),
+            "Out of bounds access on the x axis: x: 5, y: 1"
+        );
+
+        grid.set_can_expand_x(true);
+        assert!(matches!(
+            grid.update(&node, 5, 6, UpdateGridOperation::Add),
+            Err(InnerGridError::OutOfBoundsAccess { axis: Axis::Y, .. })
+        ));
+    }
+
+    #[test]
+    fn test_handles_round_trip_ids() {
+        let mut grid = InnerGrid::new(3, 3);
+        let node = Node::new("a", 0, 0, 2, 1);
+        node.update_grid(&mut grid, UpdateGridOperation::Add)
+            .unwrap();
+        grid.set(2, 2, Some("b")).unwrap();
+
+        let handle = grid.handle("a").unwrap();
+        assert_eq!(grid.resolve(handle), "a");
+        assert_eq!(grid[(0, 1)], Some(handle));
+        assert_eq!(grid.peek(1, 0), Some(Some("a")));
+        assert_eq!(grid.get_checked(2, 2).unwrap(), Some("b"));
+
+        node.update_grid(&mut grid, UpdateGridOperation::Remove)
+            .unwrap();
+        assert_eq!(grid.peek(0, 0), Some(None));
+        assert_eq!(grid.peek(2, 2), Some(Some("b")));
+        assert_eq!(grid.handle("a"), None);
+
+        // The released handle is reused by the next new id
+        grid.set(1, 1, Some("a")).unwrap();
+        assert_eq!(grid.handle("a"), Some(handle));
+        let cells: Vec<_> = grid
+            .iter_cells()
+            .filter(|(_, _, cell)| cell.is_some())
+            .collect();
+        assert_eq!(cells, vec![(1, 1, Some("a")), (2, 2, Some("b"))]);
+    }
+
+    #[test]
+    fn test_equality_ignores_handles() {
+        let mut first = InnerGrid::new(2, 2);
+        first.set(0, 0, Some("a")).unwrap();
+        first.set(1, 1, Some("b")).unwrap();
+
+        let mut second = InnerGrid::new(2, 2);
+        second.set(1, 1, Some("b")).unwrap();
+        second.set(0, 0, Some("a")).unwrap();
+
+        assert_ne!(first.handle("a"), second.handle("a"));
+        assert_eq!(first, second);
+
+        second.set(1, 1, None).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_grow_policy() {
+        let mut grid = InnerGrid::new(1, 2);
+        grid.set_auto_grow_policy(AutoGrowPolicy::Chunked(8));
+        grid.set(0, 3, Some("a")).unwrap();
+        assert_eq!(grid.rows(), 9);
+        grid.set(0, 9, Some("a")).unwrap();
+        assert_eq!(grid.rows(), 17);
+
+        let mut grid = InnerGrid::new(3, 2);
+        grid.set_auto_grow_policy(AutoGrowPolicy::Doubling);
+        grid.set(0, 3, Some("a")).unwrap();
+        assert_eq!(grid.rows(), 6);
+        grid.set(0, 20, Some("a")).unwrap();
+        assert_eq!(grid.rows(), 21);
+
+        // Growth stops at the row limit
+        grid.set_max_rows(Some(30));
+        grid.set(0, 21, Some("a")).unwrap();
+        assert_eq!(grid.rows(), 30);
+    }
+
+    /// Checks that every cell resolves and each handle counts its cells.
+    fn assert_handles_consistent(grid: &InnerGrid) {
+        let mut cells: HashMap<NodeHandle, usize> = HashMap::new();
+        for handle in grid.iter().flatten() {
+            *cells.entry(*handle).or_default() += 1;
+        }
+
+        for (index, entry) in grid.ids.iter().enumerate() {
+            let handle = NodeHandle(index as u32);
+            match entry {
+                Some(entry) => {
+                    assert_eq!(cells.get(&handle), Some(&entry.cells));
+                    assert_eq!(grid.handle(&entry.id), Some(handle));
+                }
+                None => {
+                    assert!(!cells.contains_key(&handle));
+                    assert!(grid.free.contains(&handle));
+                }
+            }
+        }
+        assert_eq!(grid.handles.len(), cells.len());
+    }
+
+    #[test]
+    fn test_handle_table_stays_consistent() {
+        let mut grid = InnerGrid::new(2, 4);
+        let a = Node::new("a".to_string(), 0, 0, 2, 2);
+        let b = Node::new("b".to_string(), 2, 0, 2, 3);
+
+        a.update_grid(&mut grid, UpdateGridOperation::Add).unwrap();
+        b.update_grid(&mut grid, UpdateGridOperation::Add).unwrap();
+        assert_handles_consistent(&grid);
+
+        // Overwriting a cell moves it from one handle to the other
+        grid.set(1, 1, Some("b")).unwrap();
+        assert_handles_consistent(&grid);
+
+        grid.expand_rows(3);
+        a.update_grid(&mut grid, UpdateGridOperation::Remove)
+            .unwrap();
+        assert_handles_consistent(&grid);
+
+        grid.truncate_rows(2);
+        grid.resize_cols(3);
+        assert_handles_consistent(&grid);
+        assert_eq!(grid.peek(1, 1), Some(Some("b")));
+        assert_eq!(grid.iter_cells().filter(|(.., id)| id.is_some()).count(), 3);
+    }
+
+    #[test]
+    fn test_handles_are_released_with_their_last_cell() {
+        let mut grid = InnerGrid::new(4, 4);
+        grid.set(3, 3, Some("kept")).unwrap();
+
+        for index in 0..100 {
+            let node = Node::new(format!("n{index}"), 0, 0, 2, 2);
+            node.update_grid(&mut grid, UpdateGridOperation::Add)
+                .unwrap();
+            grid.set(1, 1, Some("kept")).unwrap();
+            node.update_grid(&mut grid, UpdateGridOperation::Remove)
+                .unwrap();
+        }
+        assert_eq!(grid.ids.len(), 2);
+        assert_eq!(grid.handles.len(), 1);
+
+        // Dropped rows and columns give their handles back too
+        grid.set(0, 3, Some("low")).unwrap();
+        grid.truncate_rows(3);
+        grid.resize_cols(1);
+        assert_eq!(grid.handle("low"), None);
+        assert_eq!(grid.handle("kept"), None);
+        assert!(grid.handles.is_empty());
+        assert_eq!(grid.free.len(), 2);
+    }
 }
@@ -47,8 +47,16 @@
 //! ```
 //!
 //! See the `examples` directory for more usage examples.
+//!
+//! # Cargo features
+//!
+//! - `serde`: `Serialize`/`Deserialize` for nodes, changes, change events and grid snapshots
+//! - `strict-invariants`: checks after every applied batch of changes that the grid
+//!   and the items agree, panicking otherwise. The check always runs in debug
+//!   builds, this feature enables it in release builds too
 
 pub mod error;
+pub mod geometry;
 pub mod grid_engine;
 mod grid_events;
 mod inner_grid;
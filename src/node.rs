@@ -25,9 +25,10 @@
 //! Nodes are managed by the grid engine and can be added, moved, or removed from the grid.
 
 use crate::{
-    error::InnerGridError,
+    error::{InnerGridError, ItemError},
+    geometry::{Position, Size},
     inner_grid::{InnerGrid, UpdateGridOperation},
-    utils::{ForCellArgs, for_cell},
+    utils::for_cell,
 };
 
 /// Represents an item in the grid with position and dimensions.
@@ -36,10 +37,13 @@ use crate::{
 /// - Its top-left corner position (x, y)
 /// - Its dimensions (width, height)
 /// - A unique identifier
+/// - A weight, deciding which item stays in place when two items collide
+/// - A creation sequence number, ordering items by when they were added
 ///
 /// The node's area can be iterated over using the `for_cell` method,
 /// which visits each cell in the node's occupied space.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     /// Unique identifier for the node
     pub id: String,
@@ -51,10 +55,23 @@ pub struct Node {
     pub w: usize,
     /// Height of the node in grid cells
     pub h: usize,
+    /// Collision weight, lighter items are displaced by heavier ones
+    #[cfg_attr(feature = "serde", serde(default = "default_weight"))]
+    pub weight: u32,
+    /// Creation sequence number, assigned by the engine when the item is added
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seq: u64,
+}
+
+/// Weight given to nodes serialized before weights existed.
+#[cfg(feature = "serde")]
+fn default_weight() -> u32 {
+    1
 }
 
 impl Node {
-    /// Creates a new Node with the specified parameters.
+    /// Creates a new Node with the specified parameters, a weight of 1 and a
+    /// sequence number of 0.
     ///
     /// # Arguments
     ///
@@ -70,6 +87,22 @@ impl Node {
             y,
             w,
             h,
+            weight: 1,
+            seq: 0,
+        }
+    }
+
+    /// Returns a copy of this node placed at a new position.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - New x coordinate of the top-left corner
+    /// * `y` - New y coordinate of the top-left corner
+    pub(crate) fn with_position(&self, x: usize, y: usize) -> Node {
+        Node {
+            x,
+            y,
+            ..self.clone()
         }
     }
 
@@ -91,15 +124,7 @@ impl Node {
         &self,
         callback: &mut impl FnMut(usize, usize) -> Result<(), InnerGridError>,
     ) -> Result<(), InnerGridError> {
-        for_cell(
-            ForCellArgs {
-                x: self.x,
-                y: self.y,
-                w: self.w,
-                h: self.h,
-            },
-            callback,
-        )
+        for_cell(self.position(), self.size(), callback)
     }
 
     /// Updates the grid state for this node.
@@ -150,6 +175,154 @@ impl Node {
     pub fn h(&self) -> &usize {
         &self.h
     }
+
+    /// Returns the collision weight of the node.
+    pub fn weight(&self) -> &u32 {
+        &self.weight
+    }
+
+    /// Returns the creation sequence number of the node.
+    pub fn seq(&self) -> &u64 {
+        &self.seq
+    }
+
+    /// Returns the position of the node's top-left corner.
+    pub fn position(&self) -> Position {
+        Position::new(self.x, self.y)
+    }
+
+    /// Returns the dimensions of the node.
+    pub fn size(&self) -> Size {
+        Size::new(self.w, self.h)
+    }
+
+    /// Returns the number of cells covered by the node.
+    pub fn area(&self) -> usize {
+        self.w * self.h
+    }
+
+    /// Returns the x coordinate just past the node's right edge.
+    pub fn right(&self) -> usize {
+        self.x + self.w
+    }
+
+    /// Returns the y coordinate just past the node's bottom edge.
+    pub fn bottom(&self) -> usize {
+        self.y + self.h
+    }
+
+    /// Checks whether the footprints of two nodes share at least one cell.
+    ///
+    /// Nodes that only touch along an edge don't intersect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grid_engine::node::Node;
+    ///
+    /// let a = Node::new("a".to_string(), 0, 0, 2, 2);
+    /// assert!(a.intersects(&Node::new("b".to_string(), 1, 1, 2, 2)));
+    /// assert!(!a.intersects(&Node::new("c".to_string(), 2, 0, 2, 2)));
+    /// ```
+    pub fn intersects(&self, other: &Node) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+}
+
+/// Builder for [`Node`] values validated before construction.
+///
+/// Useful to build detached nodes, e.g. to feed [`GridEngine::apply_external`](crate::grid_engine::GridEngine::apply_external).
+/// Position defaults to (0, 0), size to 1x1 and weight to 1.
+///
+/// # Example
+///
+/// ```
+/// use grid_engine::node::NodeBuilder;
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let node = NodeBuilder::new("box1").position(2, 3).size(2, 2).build()?;
+/// assert_eq!(node.x(), &2);
+/// assert_eq!(node.w(), &2);
+///
+/// assert!(NodeBuilder::new("box2").size(0, 2).build().is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeBuilder {
+    id: String,
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+    weight: u32,
+}
+
+impl NodeBuilder {
+    /// Creates a new builder for a node with the given id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for the node
+    pub fn new(id: impl Into<String>) -> Self {
+        NodeBuilder {
+            id: id.into(),
+            x: 0,
+            y: 0,
+            w: 1,
+            h: 1,
+            weight: 1,
+        }
+    }
+
+    /// Sets the position of the node's top-left corner.
+    pub fn position(mut self, x: usize, y: usize) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Sets the size of the node in grid cells.
+    pub fn size(mut self, w: usize, h: usize) -> Self {
+        self.w = w;
+        self.h = h;
+        self
+    }
+
+    /// Sets the collision weight of the node.
+    pub fn weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Builds the node.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Node)` - The built node
+    /// * `Err(ItemError)` - If the width or the height is zero
+    pub fn build(self) -> Result<Node, ItemError> {
+        if self.w == 0 || self.h == 0 {
+            return Err(ItemError::InvalidDimensions {
+                w: self.w,
+                h: self.h,
+            });
+        }
+
+        Ok(Node {
+            id: self.id,
+            x: self.x,
+            y: self.y,
+            w: self.w,
+            h: self.h,
+            weight: self.weight,
+            seq: 0,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -165,6 +338,7 @@ mod tests {
         assert_eq!(node.y, 2);
         assert_eq!(node.w, 3);
         assert_eq!(node.h, 4);
+        assert_eq!(node.weight, 1);
     }
 
     #[test]
@@ -202,7 +376,11 @@ mod tests {
 
         let result = node.for_cell(&mut |x, _y| {
             if x > 0 {
-                Err(crate::error::InnerGridError::OutOfBoundsAccess { x: 0, y: 0 })
+                Err(crate::error::InnerGridError::OutOfBoundsAccess {
+                    x: 0,
+                    y: 0,
+                    axis: crate::geometry::Axis::X,
+                })
             } else {
                 Ok(())
             }
@@ -227,4 +405,53 @@ mod tests {
             "Should not visit any cells for zero dimensions"
         );
     }
+
+    #[test]
+    fn test_node_builder() {
+        let node = NodeBuilder::new("test_node")
+            .position(3, 4)
+            .size(2, 5)
+            .weight(7)
+            .build()
+            .unwrap();
+
+        assert_eq!(node.id, "test_node");
+        assert_eq!((node.x, node.y, node.w, node.h), (3, 4, 2, 5));
+        assert_eq!(node.weight, 7);
+    }
+
+    #[test]
+    fn test_node_builder_rejects_zero_dimensions() {
+        assert!(matches!(
+            NodeBuilder::new("test_node").size(0, 1).build(),
+            Err(ItemError::InvalidDimensions { w: 0, h: 1 })
+        ));
+        assert!(matches!(
+            NodeBuilder::new("test_node").size(1, 0).build(),
+            Err(ItemError::InvalidDimensions { w: 1, h: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_node_geometry() {
+        let node = Node::new("a".to_string(), 1, 2, 3, 4);
+
+        assert_eq!(node.area(), 12);
+        assert_eq!(node.right(), 4);
+        assert_eq!(node.bottom(), 6);
+    }
+
+    #[test]
+    fn test_node_intersects() {
+        let node = Node::new("a".to_string(), 2, 2, 2, 2);
+
+        let overlapping = Node::new("b".to_string(), 3, 3, 2, 2);
+        let touching = Node::new("c".to_string(), 4, 2, 2, 2);
+        let disjoint = Node::new("d".to_string(), 7, 7, 1, 1);
+
+        assert!(node.intersects(&overlapping));
+        assert!(overlapping.intersects(&node));
+        assert!(!node.intersects(&touching));
+        assert!(!node.intersects(&disjoint));
+    }
 }
@@ -20,36 +20,25 @@
 
 //! Utility functions for grid operations and cell iteration.
 //!
-//! This module provides helper functions and structures for working with grid cells,
+//! This module provides helper functions for working with grid cells,
 //! particularly for iterating over rectangular regions within the grid.
 
-use crate::error::InnerGridError;
-
-/// Arguments for iterating over a rectangular region of cells.
-///
-/// This struct defines a rectangular area in the grid by specifying:
-/// - The top-left corner position (x, y)
-/// - The width and height of the rectangle
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-pub struct ForCellArgs {
-    /// X coordinate of the top-left corner
-    pub x: usize,
-    /// Y coordinate of the top-left corner
-    pub y: usize,
-    /// Width of the rectangular region
-    pub w: usize,
-    /// Height of the rectangular region
-    pub h: usize,
-}
+use crate::{
+    error::InnerGridError,
+    geometry::{Position, Size},
+};
+use std::ops::Range;
 
 /// Iterates over cells in a rectangular region, executing a callback for each cell.
 ///
-/// This function visits each cell in the specified rectangular region in row-major order
-/// (left to right, top to bottom) and executes the provided callback for each cell.
+/// This function visits each cell in the specified rectangular region column by
+/// column (top to bottom, then left to right) and executes the provided callback
+/// for each cell.
 ///
 /// # Arguments
 ///
-/// * `args` - Defines the rectangular region to iterate over
+/// * `position` - Top-left corner of the region
+/// * `size` - Dimensions of the region
 /// * `callback` - Function to execute for each cell, receiving x and y coordinates
 ///
 /// # Returns
@@ -62,17 +51,47 @@ pub struct ForCellArgs {
 /// If the callback returns an error for any cell, iteration stops immediately
 /// and the error is propagated to the caller.
 pub fn for_cell(
-    args: ForCellArgs,
+    position: Position,
+    size: Size,
     callback: &mut impl FnMut(usize, usize) -> Result<(), InnerGridError>,
 ) -> Result<(), InnerGridError> {
-    for x in args.x..args.x + args.w {
-        for y in args.y..args.y + args.h {
+    for x in position.x..position.x + size.w {
+        for y in position.y..position.y + size.h {
             callback(x, y)?;
         }
     }
     Ok(())
 }
 
+/// Maps every index of a range, collecting the results in index order.
+///
+/// With the `parallel` feature the indices are processed on the `rayon`
+/// thread pool, otherwise one after the other. Either way the result is the
+/// same, so callers can split whole-grid scans by row without caring which
+/// path runs.
+///
+/// # Arguments
+///
+/// * `range` - Indices to map, usually rows or items
+/// * `f` - Function to apply to each index
+pub fn map_indices<T, F>(range: Range<usize>, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        range.into_par_iter().map(f).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        range.map(f).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,13 +104,7 @@ mod tests {
             Ok(())
         };
 
-        let args = ForCellArgs {
-            x: 1,
-            y: 2,
-            w: 2,
-            h: 2,
-        };
-        for_cell(args, &mut callback).unwrap();
+        for_cell(Position::new(1, 2), Size::new(2, 2), &mut callback).unwrap();
 
         assert_eq!(
             visited,
@@ -104,67 +117,34 @@ mod tests {
     fn test_for_cell_handles_zero_dimensions() {
         let mut callback = |_x, _y| Ok(());
 
-        assert!(
-            for_cell(
-                ForCellArgs {
-                    x: 0,
-                    y: 0,
-                    w: 0,
-                    h: 1
-                },
-                &mut callback
-            )
-            .is_ok()
-        );
+        assert!(for_cell(Position::new(0, 0), Size::new(0, 1), &mut callback).is_ok());
 
-        assert!(
-            for_cell(
-                ForCellArgs {
-                    x: 0,
-                    y: 0,
-                    w: 1,
-                    h: 0
-                },
-                &mut callback
-            )
-            .is_ok()
-        );
+        assert!(for_cell(Position::new(0, 0), Size::new(1, 0), &mut callback).is_ok());
 
-        assert!(
-            for_cell(
-                ForCellArgs {
-                    x: 0,
-                    y: 0,
-                    w: 0,
-                    h: 0
-                },
-                &mut callback
-            )
-            .is_ok()
-        );
+        assert!(for_cell(Position::new(0, 0), Size::new(0, 0), &mut callback).is_ok());
     }
 
     #[test]
     fn test_for_cell_propagates_error() {
         let mut callback = |x, _y| {
             if x > 1 {
-                Err(InnerGridError::OutOfBoundsAccess { x: 0, y: 0 })
+                Err(InnerGridError::OutOfBoundsAccess {
+                    x: 0,
+                    y: 0,
+                    axis: crate::geometry::Axis::X,
+                })
             } else {
                 Ok(())
             }
         };
 
-        assert!(
-            for_cell(
-                ForCellArgs {
-                    x: 1,
-                    y: 1,
-                    w: 2,
-                    h: 1
-                },
-                &mut callback
-            )
-            .is_err()
-        );
+        assert!(for_cell(Position::new(1, 1), Size::new(2, 1), &mut callback).is_err());
+    }
+
+    #[test]
+    fn test_map_indices_keeps_index_order() {
+        let mapped = map_indices(0..1000, |i| i * 2);
+
+        assert_eq!(mapped, (0..1000).map(|i| i * 2).collect::<Vec<usize>>());
     }
 }